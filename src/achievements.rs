@@ -0,0 +1,232 @@
+use std::io::Read;
+use std::time::Instant;
+
+use ggez::graphics::{draw, DrawMode, Font, Mesh, Rect, Scale, Text, TextFragment};
+use ggez::nalgebra as na;
+use ggez::{filesystem, Context, GameResult};
+
+use base_types::*;
+use constants::*;
+use game_event::{DeathCause, GameEvent};
+use theme::Palette;
+use utils::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Achievement {
+    FirstApple,
+    NoLeftTurns,
+    LongSnake,
+    FillBoard,
+    BeatTheClock,
+}
+
+impl Achievement {
+    pub fn all() -> [Achievement; 5] {
+        [
+            Achievement::FirstApple,
+            Achievement::NoLeftTurns,
+            Achievement::LongSnake,
+            Achievement::FillBoard,
+            Achievement::BeatTheClock,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstApple => "First Bite",
+            Achievement::NoLeftTurns => "Right Minded",
+            Achievement::LongSnake => "Long Boi",
+            Achievement::FillBoard => "Full House",
+            Achievement::BeatTheClock => "Beat the Clock",
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Achievement::FirstApple => "Eat an apple".to_string(),
+            Achievement::NoLeftTurns => {
+                format!("Eat {} apples without turning left", NO_LEFT_TURN_APPLES)
+            }
+            Achievement::LongSnake => format!("Reach length {}", LONG_SNAKE_LENGTH),
+            Achievement::FillBoard => "Fill the board".to_string(),
+            Achievement::BeatTheClock => "Play a Time Attack round to the end".to_string(),
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::FirstApple => "first-apple",
+            Achievement::NoLeftTurns => "no-left-turns",
+            Achievement::LongSnake => "long-snake",
+            Achievement::FillBoard => "fill-board",
+            Achievement::BeatTheClock => "beat-the-clock",
+        }
+    }
+}
+
+fn is_left_turn(from: GridVector, to: GridVector) -> bool {
+    i32::from(from.x) * i32::from(to.y) - i32::from(from.y) * i32::from(to.x) < 0
+}
+
+pub struct Achievements {
+    pub unlocked: Vec<Achievement>,
+    apples_without_left_turn: u32,
+    toasts: Vec<(Achievement, Instant)>,
+}
+
+impl Achievements {
+    pub fn parse(source: &str) -> Self {
+        let unlocked = source
+            .lines()
+            .filter_map(|line| {
+                Achievement::all()
+                    .iter()
+                    .cloned()
+                    .find(|achievement| achievement.id() == line.trim())
+            })
+            .collect();
+
+        Achievements {
+            unlocked,
+            apples_without_left_turn: 0,
+            toasts: vec![],
+        }
+    }
+
+    pub fn to_source(&self) -> String {
+        self.unlocked
+            .iter()
+            .map(|achievement| format!("{}\n", achievement.id()))
+            .collect()
+    }
+
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut content = String::new();
+        if let Ok(mut file) = filesystem::open(ctx, ACHIEVEMENTS_PATH) {
+            if file.read_to_string(&mut content).is_err() {
+                content.clear();
+            }
+        }
+
+        Achievements::parse(&content)
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(achievement) {
+            return false;
+        }
+
+        self.unlocked.push(achievement);
+        self.toasts.push((achievement, Instant::now()));
+        true
+    }
+
+    pub fn new_game(&mut self) {
+        self.apples_without_left_turn = 0;
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) -> bool {
+        match *event {
+            GameEvent::AppleEaten { length, .. } => {
+                self.apples_without_left_turn += 1;
+
+                let mut unlocked = self.unlock(Achievement::FirstApple);
+                if self.apples_without_left_turn >= NO_LEFT_TURN_APPLES {
+                    unlocked |= self.unlock(Achievement::NoLeftTurns);
+                }
+                if length >= LONG_SNAKE_LENGTH {
+                    unlocked |= self.unlock(Achievement::LongSnake);
+                }
+                unlocked
+            }
+            GameEvent::Turned { from, to } => {
+                if is_left_turn(from, to) {
+                    self.apples_without_left_turn = 0;
+                }
+                false
+            }
+            GameEvent::BoardFilled => self.unlock(Achievement::FillBoard),
+            GameEvent::Died { cause, .. } => {
+                self.apples_without_left_turn = 0;
+                if cause == DeathCause::TimeUp {
+                    self.unlock(Achievement::BeatTheClock)
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rows(&self) -> Vec<(String, String)> {
+        Achievement::all()
+            .iter()
+            .map(|&achievement| {
+                let status = if self.is_unlocked(achievement) {
+                    "Unlocked"
+                } else {
+                    "Locked"
+                };
+                (
+                    format!("{}: {}", achievement.name(), achievement.description()),
+                    status.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn update(&mut self) {
+        self.toasts
+            .retain(|&(_, unlocked_at)| since(unlocked_at) < ACHIEVEMENT_TOAST_TIME);
+    }
+
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        palette: Palette,
+        area: Rect,
+        font: Font,
+    ) -> GameResult<()> {
+        for (i, &(achievement, unlocked_at)) in self.toasts.iter().enumerate() {
+            let t = since(unlocked_at);
+            let alpha = ((ACHIEVEMENT_TOAST_TIME - t) / ACHIEVEMENT_TOAST_FADE)
+                .min(t / ACHIEVEMENT_TOAST_FADE)
+                .min(1.0) as f32;
+
+            let rect = Rect::new(
+                area.x + (area.w - ACHIEVEMENT_TOAST_WIDTH) / 2.0,
+                INFO_BAR_HIGHT + 20.0 + i as f32 * (ACHIEVEMENT_TOAST_HIGHT + 10.0),
+                ACHIEVEMENT_TOAST_WIDTH,
+                ACHIEVEMENT_TOAST_HIGHT,
+            );
+
+            // Draw background
+            let background =
+                Mesh::new_rectangle(ctx, DrawMode::fill(), rect, fade(palette.foreground, alpha))?;
+            draw(ctx, &background, (na::Point2::new(0.0, 0.0),))?;
+
+            // Draw text
+            let lines = [
+                format!("Achievement unlocked: {}", achievement.name()),
+                achievement.description(),
+            ];
+            for (line_index, line) in lines.iter().enumerate() {
+                let frag = TextFragment::new(line.as_str()).color(fade(palette.background, alpha));
+                let mut text = Text::new(frag);
+                text.set_font(font, Scale::uniform(FONT_DEFAULT_SIZE));
+
+                let dest = na::Point2::new(
+                    rect.x + (rect.w - text.width(ctx) as f32) / 2.0,
+                    rect.y + 6.0 + line_index as f32 * (FONT_DEFAULT_SIZE + 4.0),
+                );
+                draw(ctx, &text, (dest,))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,15 @@
 use base_types::*;
-use std::time::Instant;
 
 pub struct Apple {
     pub position: GridVector,
-    pub spawned_at: Instant,
+    pub spawned_at: f64,
 }
 
 impl Apple {
-    pub fn new(pos: GridVector) -> Self {
+    pub fn new(pos: GridVector, time: f64) -> Self {
         Apple {
             position: pos,
-            spawned_at: Instant::now(),
+            spawned_at: time,
         }
     }
 }
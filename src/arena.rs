@@ -0,0 +1,322 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use apple::Apple;
+use base_types::*;
+use constants::*;
+use game::spawn_apple;
+use game_event::{DeathCause, GameEvent};
+use grid::*;
+use level::Level;
+use pathfinding::{step, DistanceMap, DIRECTIONS};
+use snake::Snake;
+
+pub struct Player {
+    pub name: String,
+    pub snake: Snake,
+    pub connected: bool,
+}
+
+impl Player {
+    pub fn is_alive(&self) -> bool {
+        self.snake.lost_at.is_none()
+    }
+}
+
+// A round with several snakes on one board, stepped in lockstep by every peer
+pub struct Arena {
+    pub grid: GridVector,
+    pub players: Vec<Player>,
+    pub apple: Apple,
+    pub level: Level,
+    pub seed: u64,
+    pub tick: u64,
+    pub time: f64,
+    pub speed: f64,
+    pub events: Vec<GameEvent>,
+    rng: StdRng,
+}
+
+fn start_position(grid: GridVector, index: usize, count: usize) -> (GridVector, GridVector) {
+    let area = play_area(grid);
+    let row = area.pos.y + area.size.y * (index as GridUnit + 1) / (count as GridUnit + 1);
+    // Alternate sides so neighbouring snakes face away from each other
+    match index % 2 {
+        0 => (
+            GridVector::new(area.pos.x + area.size.x / 4, row),
+            GridVector::new(1, 0),
+        ),
+        _ => (
+            GridVector::new(area.pos.x + area.size.x * 3 / 4, row),
+            GridVector::new(-1, 0),
+        ),
+    }
+}
+
+impl Arena {
+    pub fn new(grid: GridVector, names: &[String], seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let level = Level::corners(grid);
+
+        let players: Vec<Player> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let (start, velocity) = start_position(grid, index, names.len());
+                let mut snake = Snake::at(start);
+                snake.velocity = velocity;
+                Player {
+                    name: name.clone(),
+                    snake,
+                    connected: true,
+                }
+            })
+            .collect();
+
+        let mut arena = Arena {
+            grid,
+            players,
+            apple: Apple::new(GridVector::new(0, 0), 0.0),
+            level,
+            seed,
+            tick: 0,
+            time: 0.0,
+            speed: INITIAL_SPEED,
+            events: vec![],
+            rng: StdRng::seed_from_u64(0),
+        };
+        if let Some(apple) = spawn_apple(&mut rng, grid, &arena.level, &arena.occupied_cells(), 0.0)
+        {
+            arena.apple = apple;
+        }
+        arena.rng = rng;
+        arena
+    }
+
+    fn occupied_cells(&self) -> Vec<GridVector> {
+        let mut cells: Vec<GridVector> = self
+            .players
+            .iter()
+            .filter(|player| player.is_alive())
+            .flat_map(|player| player.snake.tail.iter().cloned())
+            .collect();
+        cells.extend(self.level.stones.iter().map(|stone| stone.pos));
+        cells
+    }
+
+    pub fn alive(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|player| player.is_alive())
+            .count()
+    }
+
+    pub fn is_over(&self) -> bool {
+        let alive = self.alive();
+        alive == 0 || (self.players.len() > 1 && alive == 1)
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        if self.is_over() {
+            self.players.iter().position(|player| player.is_alive())
+        } else {
+            None
+        }
+    }
+
+    pub fn disconnect(&mut self, id: usize) {
+        if let Some(player) = self.players.get_mut(id) {
+            player.connected = false;
+            if player.snake.lost_at.is_none() {
+                player.snake.lost_at = Some(self.time);
+            }
+        }
+    }
+
+    pub fn step(&mut self, directions: &[GridVector]) {
+        if self.is_over() {
+            return;
+        }
+
+        let time = self.time;
+        let zero = GridVector::new(0, 0);
+
+        for (player, &direction) in self.players.iter_mut().zip(directions) {
+            let snake = &mut player.snake;
+            if direction != zero && direction + snake.velocity != zero {
+                snake.velocity = direction;
+            }
+        }
+
+        let grid = self.grid;
+        let portals = &self.level.portals;
+        let heads: Vec<Option<GridVector>> = self
+            .players
+            .iter()
+            .map(|player| {
+                if player.is_alive() {
+                    Some(step(
+                        grid,
+                        player.snake.head(),
+                        player.snake.velocity,
+                        portals,
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+            let snake = &mut player.snake;
+            snake.previous_tail = snake.tail.clone();
+            if snake.grow > 0 {
+                snake.grow -= 1;
+            } else {
+                snake.tail.remove(0);
+            }
+        }
+
+        let mut causes = vec![None; self.players.len()];
+        for (id, head) in heads.iter().enumerate() {
+            let head = match *head {
+                Some(head) => head,
+                None => continue,
+            };
+
+            for (other_id, other) in self.players.iter().enumerate() {
+                if other.is_alive() && other.snake.tail.contains(&head) {
+                    causes[id] = Some(if other_id == id {
+                        DeathCause::SelfCollision
+                    } else {
+                        DeathCause::OtherSnake
+                    });
+                }
+            }
+            let head_on = heads
+                .iter()
+                .enumerate()
+                .any(|(other_id, &other)| other_id != id && other == Some(head));
+            if head_on {
+                causes[id] = Some(DeathCause::OtherSnake);
+            }
+            // Blinking stones can also appear on the body
+            let tail = &self.players[id].snake.tail;
+            let hit_stone = self.level.stones.iter().any(|stone| {
+                (stone.pos == head || tail.contains(&stone.pos)) && stone.is_active(time, 0)
+            });
+            if hit_stone {
+                causes[id] = Some(DeathCause::Stone);
+            }
+        }
+
+        for (id, head) in heads.iter().enumerate() {
+            let head = match *head {
+                Some(head) => head,
+                None => continue,
+            };
+            let snake = &mut self.players[id].snake;
+
+            match causes[id] {
+                Some(cause) => {
+                    snake.tail = snake.previous_tail.clone();
+                    snake.lost_at = Some(time);
+                    self.events.push(GameEvent::Collision { position: head });
+                    self.events.push(GameEvent::Died {
+                        tail: snake.tail.clone(),
+                        cause,
+                        score: snake.score,
+                        time,
+                    });
+                }
+                None => snake.tail.push(head),
+            }
+        }
+
+        let eater = self
+            .players
+            .iter()
+            .position(|player| player.is_alive() && player.snake.head() == self.apple.position);
+        if let Some(id) = eater {
+            {
+                let snake = &mut self.players[id].snake;
+                snake.score += 1;
+                snake.grow += GROW_PER_APPLE;
+                self.events.push(GameEvent::AppleEaten {
+                    position: self.apple.position,
+                    points: 1,
+                    length: snake.tail.len(),
+                });
+            }
+            self.speed -= self.speed / SPEED_INCREASE_FRACTION;
+            let conflicts = self.occupied_cells();
+            // Without a free cell the apple waits under the snake for the tail to pass
+            if let Some(apple) =
+                spawn_apple(&mut self.rng, self.grid, &self.level, &conflicts, time)
+            {
+                self.apple = apple;
+            }
+        }
+
+        self.tick += 1;
+        self.time += self.speed;
+    }
+
+    // Steers towards the apple, used by headless players
+    pub fn bot_direction(&self, id: usize) -> GridVector {
+        let snake = &self.players[id].snake;
+        let blocked = self.occupied_cells();
+        let distances = DistanceMap::new(
+            self.grid,
+            self.apple.position,
+            &blocked,
+            &self.level.portals,
+        );
+
+        let mut best = None;
+        for &direction in &DIRECTIONS {
+            if direction + snake.velocity == GridVector::new(0, 0) {
+                continue;
+            }
+            let next = step(self.grid, snake.head(), direction, &self.level.portals);
+            if blocked.contains(&next) {
+                continue;
+            }
+
+            // Cells the apple can't be reached from come last
+            let distance = distances.get(next);
+            let distance = (distance.is_none(), distance.unwrap_or(0));
+            let is_better = match best {
+                Some((_, other)) => distance < other,
+                None => true,
+            };
+            if is_better {
+                best = Some((direction, distance));
+            }
+        }
+
+        best.map_or(snake.velocity, |(direction, _)| direction)
+    }
+
+    // Lets peers compare their simulations
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut mix = |value: i64| {
+            hash ^= value as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        };
+
+        mix(self.tick as i64);
+        mix(i64::from(self.apple.position.x));
+        mix(i64::from(self.apple.position.y));
+        for player in &self.players {
+            mix(i64::from(player.snake.score));
+            mix(if player.is_alive() { 1 } else { 0 });
+            for pos in &player.snake.tail {
+                mix(i64::from(pos.x));
+                mix(i64::from(pos.y));
+            }
+        }
+        hash
+    }
+}
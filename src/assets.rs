@@ -1,12 +1,44 @@
 use ggez::*;
+use std::io::Read;
+
+use constants::*;
+
+pub const BUILT_IN_FONT: &[u8] = include_bytes!("../resources/SHPinscher-Regular.ttf");
 
 pub struct Assets {
     pub font: graphics::Font,
 }
 
+fn built_in_font(ctx: &mut Context) -> graphics::Font {
+    match graphics::Font::new_glyph_font_bytes(ctx, BUILT_IN_FONT) {
+        Ok(font) => font,
+        Err(err) => {
+            eprintln!("Failed to load font, using the default font: {}", err);
+            graphics::Font::default()
+        }
+    }
+}
+
+fn skin_font(ctx: &mut Context) -> GameResult<graphics::Font> {
+    let mut bytes = vec![];
+    filesystem::open(ctx, SKIN_FONT_PATH)?.read_to_end(&mut bytes)?;
+    graphics::Font::new_glyph_font_bytes(ctx, &bytes)
+}
+
 impl Assets {
-    pub fn load(ctx: &mut Context) -> GameResult<Self> {
-        let font = graphics::Font::new(ctx, "/SHPinscher-Regular.ttf")?;
-        Ok(Assets { font })
+    pub fn load(ctx: &mut Context) -> Self {
+        let font = if filesystem::exists(ctx, SKIN_FONT_PATH) {
+            match skin_font(ctx) {
+                Ok(font) => font,
+                Err(err) => {
+                    eprintln!("Failed to load {}: {}", SKIN_FONT_PATH, err);
+                    built_in_font(ctx)
+                }
+            }
+        } else {
+            built_in_font(ctx)
+        };
+
+        Assets { font }
     }
 }
@@ -36,6 +36,7 @@ impl ops::Sub<GridVector> for GridVector {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
 pub struct GridArea {
     pub pos: GridVector,
     pub size: GridVector,
@@ -0,0 +1,138 @@
+extern crate clap;
+extern crate ggez_snake;
+
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use clap::{App, Arg};
+
+use ggez_snake::constants::*;
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::http::{self, Request};
+use ggez_snake::leaderboard::{self, Board};
+
+fn get_scores(board: &Mutex<Board>, request: &Request) -> (u16, String) {
+    let mode = request
+        .param("mode")
+        .and_then(|id| GameMode::all().iter().cloned().find(|mode| mode.id() == id));
+    let mode = match mode {
+        Some(mode) => mode,
+        None => return (400, "unknown or missing mode\n".to_string()),
+    };
+    let limit = request
+        .param("limit")
+        .and_then(|limit| limit.parse().ok())
+        .unwrap_or(LEADERBOARD_SIZE);
+
+    let board = board.lock().expect("the board lock is not poisoned");
+    let body = board
+        .top(mode, limit)
+        .iter()
+        .map(|entry| format!("{} {}\n", entry.score, entry.name))
+        .collect();
+    (200, body)
+}
+
+fn post_score(board: &Mutex<Board>, request: &Request) -> (u16, String) {
+    let (name, replay) = match leaderboard::parse_submission(&request.body) {
+        Ok(submission) => submission,
+        Err(err) => return (400, format!("{}\n", err)),
+    };
+
+    let mut entry = match leaderboard::validate(&replay) {
+        Ok(entry) => entry,
+        Err(err) => return (422, format!("{}\n", err)),
+    };
+    entry.name = name;
+
+    println!(
+        "{} scored {} in {}",
+        entry.name,
+        entry.score,
+        entry.mode.id()
+    );
+    // Only the insert holds the lock, the replay was already checked
+    let mut board = board.lock().expect("the board lock is not poisoned");
+    match board.insert(entry) {
+        Ok(rank) => (200, format!("rank {}\n", rank)),
+        Err(err) => {
+            eprintln!("error: failed to save leaderboard: {}", err);
+            (500, "failed to save score\n".to_string())
+        }
+    }
+}
+
+fn handle(board: &Mutex<Board>, stream: &mut TcpStream) {
+    if let Err(err) = stream.set_read_timeout(Some(HTTP_TIMEOUT)) {
+        eprintln!("warning: failed to set timeout: {}", err);
+    }
+
+    let (status, body) = match http::read_request(stream) {
+        Ok(ref request) => match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/scores") => get_scores(board, request),
+            ("POST", "/scores") => post_score(board, request),
+            (_, "/scores") => (405, "use GET or POST\n".to_string()),
+            _ => (404, "not found\n".to_string()),
+        },
+        Err(err) => (400, format!("{}\n", err)),
+    };
+
+    if let Err(err) = http::write_response(stream, status, &body) {
+        eprintln!("warning: failed to respond: {}", err);
+    }
+}
+
+fn main() {
+    let matches = App::new("leaderboard-server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Stores snake scores and checks each one by replaying it")
+        .arg(
+            Arg::with_name("address")
+                .long("address")
+                .value_name("HOST:PORT")
+                .default_value(LEADERBOARD_ADDRESS)
+                .help("Address to listen on"),
+        )
+        .arg(
+            Arg::with_name("file")
+                .long("file")
+                .value_name("FILE")
+                .default_value(LEADERBOARD_FILE)
+                .help("File the scores are stored in"),
+        )
+        .get_matches();
+
+    let address = matches.value_of("address").unwrap_or(LEADERBOARD_ADDRESS);
+    let file = matches.value_of_os("file").unwrap_or_default();
+
+    let board = match Board::load(file) {
+        Ok(board) => Arc::new(Mutex::new(board)),
+        Err(err) => {
+            eprintln!("error: failed to load leaderboard: {}", err);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: failed to listen on {}: {}", address, err);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+    println!("Listening on {}", address);
+
+    // Every request gets its own thread, so a long replay or a slow client
+    // doesn't hold up the others
+    for stream in listener.incoming() {
+        match stream {
+            Ok(mut stream) => {
+                let board = Arc::clone(&board);
+                thread::spawn(move || handle(&board, &mut stream));
+            }
+            Err(err) => eprintln!("warning: failed to accept connection: {}", err),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use clap::{App, Arg, ArgMatches, Error, ErrorKind};
+use std::path::PathBuf;
+
+use base_types::*;
+use constants::*;
+use game_mode::GameMode;
+use settings::SpeedPreset;
+use theme::Theme;
+
+pub struct Options {
+    pub seed: Option<u64>,
+    pub level: Option<PathBuf>,
+    pub mode: Option<GameMode>,
+    pub speed: Option<SpeedPreset>,
+    pub grid: Option<GridVector>,
+    pub replay: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub resources: Option<PathBuf>,
+    pub leaderboard: Option<String>,
+    pub host: Option<String>,
+    pub join: Option<String>,
+    pub name: Option<String>,
+    pub players: usize,
+    pub stream: Option<String>,
+    pub spectate: Option<String>,
+    pub fullscreen: bool,
+    pub headless: bool,
+    pub tui: bool,
+    pub screenshot: Option<PathBuf>,
+    pub tick: Option<u64>,
+    pub export: Option<PathBuf>,
+    pub scale: u32,
+    pub fps: Option<f64>,
+    pub theme: Option<Theme>,
+}
+
+fn parse_mode(value: &str) -> Option<GameMode> {
+    GameMode::all()
+        .iter()
+        .cloned()
+        .find(|mode| mode.id() == value)
+}
+
+fn parse_speed(value: &str) -> Option<SpeedPreset> {
+    SpeedPreset::all()
+        .iter()
+        .cloned()
+        .find(|speed| speed.id() == value)
+}
+
+fn parse_theme(value: &str) -> Option<Theme> {
+    Theme::all(false)
+        .into_iter()
+        .find(|theme| theme.id() == value)
+}
+
+fn parse_grid(value: &str) -> Result<GridVector, String> {
+    let lowercase = value.to_lowercase();
+    let mut parts = lowercase.splitn(2, 'x');
+    let (width, height) = match (parts.next(), parts.next()) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return Err(format!("expected WIDTHxHEIGHT, found `{}`", value)),
+    };
+
+    let parse = |part: &str| {
+        part.parse::<GridUnit>()
+            .ok()
+            .filter(|size| (MIN_GRID_SIZE..=MAX_GRID_SIZE).contains(size))
+            .ok_or_else(|| {
+                format!(
+                    "grid sides must be between {} and {}, found `{}`",
+                    MIN_GRID_SIZE, MAX_GRID_SIZE, part
+                )
+            })
+    };
+
+    Ok(GridVector::new(parse(width)?, parse(height)?))
+}
+
+fn validate_seed(value: String) -> Result<(), String> {
+    value
+        .parse::<u64>()
+        .map(|_| ())
+        .map_err(|_| format!("expected a positive number, found `{}`", value))
+}
+
+fn validate_tick(value: String) -> Result<(), String> {
+    value
+        .parse::<u64>()
+        .map(|_| ())
+        .map_err(|_| format!("expected a tick number, found `{}`", value))
+}
+
+fn validate_scale(value: String) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(scale) if (1..=EXPORT_MAX_SCALE).contains(&scale) => Ok(()),
+        _ => Err(format!(
+            "expected a number between 1 and {}, found `{}`",
+            EXPORT_MAX_SCALE, value
+        )),
+    }
+}
+
+fn validate_fps(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(fps) if fps > 0.0 && fps <= 100.0 => Ok(()),
+        _ => Err(format!(
+            "expected a frame rate between 0 and 100, found `{}`",
+            value
+        )),
+    }
+}
+
+fn validate_grid(value: String) -> Result<(), String> {
+    parse_grid(&value).map(|_| ())
+}
+
+fn validate_players(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(players) if (1..=MAX_PLAYERS).contains(&players) => Ok(()),
+        _ => Err(format!(
+            "expected a number between 1 and {}, found `{}`",
+            MAX_PLAYERS, value
+        )),
+    }
+}
+
+fn app<'a, 'b>(modes: &'a [&'a str], speeds: &'a [&'a str], themes: &'a [&'a str]) -> App<'a, 'b> {
+    App::new("ggez-snake")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("A snake game")
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("NUMBER")
+                .help("Seed for apple, power-up and level generation")
+                .validator(validate_seed),
+        )
+        .arg(
+            Arg::with_name("level")
+                .long("level")
+                .value_name("FILE")
+                .help("Plays the level from the given file"),
+        )
+        .arg(
+            Arg::with_name("mode")
+                .long("mode")
+                .value_name("MODE")
+                .possible_values(modes)
+                .help("Game mode to start in"),
+        )
+        .arg(
+            Arg::with_name("speed")
+                .long("speed")
+                .value_name("SPEED")
+                .possible_values(speeds)
+                .help("Initial snake speed"),
+        )
+        .arg(
+            Arg::with_name("grid")
+                .long("grid")
+                .value_name("WxH")
+                .help("Grid size in tiles, e.g. 48x30")
+                .validator(validate_grid),
+        )
+        .arg(
+            Arg::with_name("replay")
+                .long("replay")
+                .value_name("FILE")
+                .help("Plays back a recorded game")
+                .conflicts_with_all(&["seed", "level", "mode", "speed", "grid"]),
+        )
+        .arg(
+            Arg::with_name("record")
+                .long("record")
+                .value_name("FILE")
+                .help("Records the last round to the given file"),
+        )
+        .arg(
+            Arg::with_name("resources")
+                .long("resources")
+                .value_name("DIR")
+                .help("Searches the given directory for a skin font, theme and levels"),
+        )
+        .arg(
+            Arg::with_name("leaderboard")
+                .long("leaderboard")
+                .value_name("HOST:PORT")
+                .help("Submits scores to and shows the leaderboard server at the given address"),
+        )
+        .arg(
+            Arg::with_name("host")
+                .long("host")
+                .value_name("HOST:PORT")
+                .help("Hosts a multiplayer match on the given address")
+                .conflicts_with_all(&["replay", "join"]),
+        )
+        .arg(
+            Arg::with_name("join")
+                .long("join")
+                .value_name("HOST:PORT")
+                .help("Joins the multiplayer match hosted on the given address")
+                .conflicts_with("replay"),
+        )
+        .arg(
+            Arg::with_name("name")
+                .long("name")
+                .value_name("NAME")
+                .help("Player name in multiplayer matches, defaults to the profile name"),
+        )
+        .arg(
+            Arg::with_name("players")
+                .long("players")
+                .value_name("COUNT")
+                .help("Players to wait for before a headless host starts the match [default: 2]")
+                .validator(validate_players)
+                .requires("host"),
+        )
+        .arg(
+            Arg::with_name("stream")
+                .long("stream")
+                .value_name("HOST:PORT")
+                .help("Publishes the board after every tick to spectators on the given address"),
+        )
+        .arg(
+            Arg::with_name("spectate")
+                .long("spectate")
+                .value_name("HOST:PORT")
+                .help("Watches the game streamed from the given address")
+                .conflicts_with_all(&["replay", "host", "join", "stream", "headless"]),
+        )
+        .arg(
+            Arg::with_name("fullscreen")
+                .long("fullscreen")
+                .help("Starts in fullscreen"),
+        )
+        .arg(
+            Arg::with_name("headless")
+                .long("headless")
+                .help(
+                    "Simulates the replay, or plays the multiplayer match with a bot, \
+                     without a window and prints the result",
+                )
+                .conflicts_with("fullscreen"),
+        )
+        .arg(
+            Arg::with_name("tui")
+                .long("tui")
+                .help("Plays in the terminal instead of a window")
+                .conflicts_with_all(&["headless", "fullscreen", "host", "join", "spectate"]),
+        )
+        .arg(
+            Arg::with_name("screenshot")
+                .long("screenshot")
+                .value_name("FILE")
+                .help("Saves the replay as a PNG image, after its last tick or at --tick")
+                .requires("replay")
+                .conflicts_with_all(&["headless", "tui"]),
+        )
+        .arg(
+            Arg::with_name("tick")
+                .long("tick")
+                .value_name("TICK")
+                .help("Tick of the replay to take the screenshot at")
+                .requires("screenshot")
+                .validator(validate_tick),
+        )
+        .arg(
+            Arg::with_name("export")
+                .long("export")
+                .value_name("PATH")
+                .help(
+                    "Renders the replay to an animated GIF if PATH ends in .gif, \
+                     otherwise to numbered PNGs in the directory PATH",
+                )
+                .requires("replay")
+                .conflicts_with_all(&["headless", "tui", "screenshot"]),
+        )
+        .arg(
+            Arg::with_name("scale")
+                .long("scale")
+                .value_name("FACTOR")
+                .help("Scales screenshots and exports by a whole number [default: 1]")
+                .validator(validate_scale),
+        )
+        .arg(
+            Arg::with_name("fps")
+                .long("fps")
+                .value_name("FPS")
+                .help("Frames per second of an export [default: one frame per tick]")
+                .requires("export")
+                .validator(validate_fps),
+        )
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .value_name("THEME")
+                .possible_values(themes)
+                .help("Theme of screenshots and exports"),
+        )
+}
+
+fn options(matches: &ArgMatches) -> Options {
+    Options {
+        seed: matches.value_of("seed").and_then(|seed| seed.parse().ok()),
+        level: matches.value_of_os("level").map(PathBuf::from),
+        mode: matches.value_of("mode").and_then(parse_mode),
+        speed: matches.value_of("speed").and_then(parse_speed),
+        grid: matches
+            .value_of("grid")
+            .and_then(|grid| parse_grid(grid).ok()),
+        replay: matches.value_of_os("replay").map(PathBuf::from),
+        record: matches.value_of_os("record").map(PathBuf::from),
+        resources: matches.value_of_os("resources").map(PathBuf::from),
+        leaderboard: matches.value_of("leaderboard").map(String::from),
+        host: matches.value_of("host").map(String::from),
+        join: matches.value_of("join").map(String::from),
+        name: matches.value_of("name").map(String::from),
+        players: matches
+            .value_of("players")
+            .and_then(|players| players.parse().ok())
+            .unwrap_or(2),
+        stream: matches.value_of("stream").map(String::from),
+        spectate: matches.value_of("spectate").map(String::from),
+        fullscreen: matches.is_present("fullscreen"),
+        headless: matches.is_present("headless"),
+        tui: matches.is_present("tui"),
+        screenshot: matches.value_of_os("screenshot").map(PathBuf::from),
+        tick: matches.value_of("tick").and_then(|tick| tick.parse().ok()),
+        export: matches.value_of_os("export").map(PathBuf::from),
+        scale: matches
+            .value_of("scale")
+            .and_then(|scale| scale.parse().ok())
+            .unwrap_or(1),
+        fps: matches.value_of("fps").and_then(|fps| fps.parse().ok()),
+        theme: matches.value_of("theme").and_then(parse_theme),
+    }
+}
+
+pub fn parse_args() -> Result<Options, Error> {
+    let modes: Vec<&str> = GameMode::all().iter().map(|mode| mode.id()).collect();
+    let speeds: Vec<&str> = SpeedPreset::all().iter().map(|speed| speed.id()).collect();
+    let themes: Vec<&str> = Theme::all(false).iter().map(|theme| theme.id()).collect();
+
+    let matches = app(&modes, &speeds, &themes).get_matches_safe()?;
+    let options = options(&matches);
+
+    if options.headless
+        && options.replay.is_none()
+        && options.host.is_none()
+        && options.join.is_none()
+    {
+        return Err(Error::with_description(
+            "--headless requires --replay, --host or --join",
+            ErrorKind::MissingRequiredArgument,
+        ));
+    }
+    if (matches.is_present("scale") || options.theme.is_some())
+        && options.screenshot.is_none()
+        && options.export.is_none()
+    {
+        return Err(Error::with_description(
+            "--scale and --theme require --screenshot or --export",
+            ErrorKind::MissingRequiredArgument,
+        ));
+    }
+    Ok(options)
+}
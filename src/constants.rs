@@ -1,42 +1,43 @@
+use std::time::Duration;
+
 use base_types::*;
-use ggez::graphics::Color;
 
 //--- Grid ---//
 pub const GRID_TILE_SIZE: f32 = 20.0;
-pub const GRID_SIZE: GridVector = GridVector { x: 48, y: 30 };
-pub static GRID_AREA: GridArea = GridArea {
-    pos: GridVector { x: 0, y: 0 },
-    size: GRID_SIZE,
-};
-pub static PLAY_AREA: GridArea = GridArea {
-    pos: GridVector { x: 2, y: 2 },
-    size: GridVector {
-        x: GRID_SIZE.x - 4,
-        y: GRID_SIZE.y - 4,
-    },
-};
+pub const DEFAULT_GRID_SIZE: GridVector = GridVector { x: 48, y: 30 };
+pub const MIN_GRID_SIZE: GridUnit = 16;
+pub const MAX_GRID_SIZE: GridUnit = 200;
 
 //--- Visual ---//
 pub const APPLE_BLINK_TIME: f64 = 0.7;
 pub const BLINK_INTERVAL: f64 = 0.08;
 pub const BLINK_LENGTH_AFTER_DEATH: f64 = 0.6;
-pub const COLOR_BACKGROUND: Color = Color {
-    r: 0.0078,
-    g: 0.0569,
-    b: 0.0762,
-    a: 1.0,
-};
-pub const COLOR_FOREGROUND: Color = Color {
-    r: 1.0,
-    g: 1.0,
-    b: 1.0,
-    a: 1.0,
-};
+pub const BUTTON_HOVER_TIME: f64 = 0.12;
 pub const FONT_DEFAULT_SIZE: f32 = 22.0;
 pub const FONT_GAME_OVER_SIZE: f32 = 80.0;
+pub const GAME_OVER_FADE_TIME: f64 = 0.5;
+pub const GAME_OVER_SLIDE: f32 = 30.0;
+pub const GAME_OVER_STAGGER: f64 = 0.08;
 pub const GAME_OVER_TIMEOUT: f64 = 0.9;
 pub const GRID_TILE_PADDING: f32 = 1.0;
 pub const INFO_BAR_HIGHT: f32 = 40.0;
+pub const POWER_UP_BAR_WIDTH: f32 = 60.0;
+pub const POWER_UP_EXPIRE_WARNING: f64 = 2.0;
+pub const SMOOTH_MOVEMENT: bool = true;
+
+//--- Effects ---//
+pub const EFFECTS_ENABLED: bool = true;
+pub const DEATH_EXPLOSION_INTERVAL: f64 = 0.03;
+pub const DEATH_PARTICLE_COUNT: usize = 6;
+pub const PARTICLE_COUNT: usize = 12;
+pub const PARTICLE_GRAVITY: f32 = 300.0;
+pub const PARTICLE_LIFETIME: f64 = 0.5;
+pub const PARTICLE_SIZE: f32 = 4.0;
+pub const PARTICLE_SPEED: f32 = 120.0;
+pub const POPUP_LIFETIME: f64 = 0.8;
+pub const POPUP_RISE: f32 = 30.0;
+pub const SCREEN_SHAKE_STRENGTH: f32 = 6.0;
+pub const SCREEN_SHAKE_TIME: f64 = 0.25;
 
 //--- Game play ---//
 pub const INITIAL_SIZE: GridUnit = 2;
@@ -45,3 +46,101 @@ pub const INITIAL_VELOCITY: GridVector = GridVector { x: 0, y: 0 };
 
 pub const GROW_PER_APPLE: GridUnit = 3;
 pub const SPEED_INCREASE_FRACTION: f64 = 36.0;
+
+pub const POWER_UP_SPAWN_CHANCE: f64 = 0.3;
+pub const POWER_UP_LIFETIME: f64 = 10.0;
+pub const GHOST_DURATION: f64 = 6.0;
+pub const SHIELD_DURATION: f64 = 15.0;
+pub const MAGNET_DURATION: f64 = 8.0;
+pub const MAGNET_RANGE: u32 = 6;
+pub const MULTIPLIER_DURATION: f64 = 10.0;
+pub const SCORE_MULTIPLIER: u32 = 2;
+
+//--- Game modes ---//
+pub const HIGH_SCORE_TABLE_SIZE: usize = 5;
+pub const TIME_ATTACK_LIMIT: f64 = 120.0;
+pub const SURVIVAL_APPLE_LIFETIME: f64 = 6.0;
+pub const SURVIVAL_SHRINK_PER_MISSED_APPLE: GridUnit = 2;
+
+//--- Input ---//
+pub const GAMEPAD_STICK_THRESHOLD: f32 = 0.5;
+
+//--- Settings ---//
+pub const SETTINGS_FILE: &str = "settings.cfg";
+pub const DEFAULT_VOLUME: f32 = 0.8;
+
+//--- Profiles ---//
+pub const PROFILES_DIR: &str = "/profiles";
+pub const ACTIVE_PROFILE_PATH: &str = "/profile.txt";
+pub const DEFAULT_PROFILE_NAME: &str = "Player";
+pub const PROFILE_NAME_MAX_LENGTH: usize = 16;
+
+//--- Statistics ---//
+pub const STATS_PATH: &str = "/stats.txt";
+
+//--- Achievements ---//
+pub const ACHIEVEMENTS_PATH: &str = "/achievements.txt";
+pub const ACHIEVEMENT_TOAST_TIME: f64 = 3.0;
+pub const ACHIEVEMENT_TOAST_FADE: f64 = 0.3;
+pub const ACHIEVEMENT_TOAST_WIDTH: f32 = 460.0;
+pub const ACHIEVEMENT_TOAST_HIGHT: f32 = 60.0;
+pub const NO_LEFT_TURN_APPLES: u32 = 10;
+pub const LONG_SNAKE_LENGTH: usize = 100;
+
+//--- Leaderboard ---//
+pub const LEADERBOARD_ADDRESS: &str = "127.0.0.1:7878";
+pub const LEADERBOARD_FILE: &str = "leaderboard.txt";
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const HTTP_MAX_BODY: usize = 1 << 20;
+// For the request or status line and all headers together
+pub const HTTP_MAX_HEADERS: usize = 8 << 10;
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+//--- Multiplayer ---//
+pub const MULTIPLAYER_ADDRESS: &str = "127.0.0.1:7700";
+pub const MAX_PLAYERS: usize = 4;
+pub const MULTIPLAYER_TICK_LIMIT: u64 = 5000;
+pub const NET_POLL_INTERVAL: Duration = Duration::from_millis(1);
+pub const NET_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+pub const NET_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// How many ticks ahead of the match a guest may send inputs
+pub const NET_MAX_INPUT_LEAD: u64 = 64;
+// Longer lines drop the peer, the longest honest one is a full lobby
+pub const NET_MAX_LINE: usize = 1 << 10;
+
+//--- Streaming ---//
+pub const STREAM_ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+pub const STREAM_WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+//--- Replays ---//
+// Longer than any real run, keeps a hand written replay from simulating forever
+pub const MAX_REPLAY_TICKS: u64 = 1_000_000;
+
+//--- Export ---//
+pub const EXPORT_MAX_SCALE: u32 = 8;
+pub const EXPORT_FINAL_HOLD: f64 = 1.5;
+
+//--- Resources ---//
+pub const SKIN_FONT_PATH: &str = "/font.ttf";
+pub const SKIN_THEME_PATH: &str = "/theme.cfg";
+
+//--- Exit codes ---//
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_REPLAY_MISMATCH: i32 = 3;
+pub const EXIT_NO_DISPLAY: i32 = 4;
+pub const EXIT_STARTUP: i32 = 5;
+pub const EXIT_RUNTIME: i32 = 6;
+
+//--- Editor ---//
+pub const CUSTOM_LEVEL_NAME: &str = "Custom";
+pub const CUSTOM_LEVEL_PATH: &str = "/levels/custom.txt";
+pub const EDITOR_MESSAGE_TIME: f64 = 3.0;
+
+//--- Level generator ---//
+pub const LEVEL_GEN_DENSITY: f32 = 0.08;
+pub const LEVEL_GEN_CORRIDOR_WIDTH: GridUnit = 2;
+pub const LEVEL_GEN_SAFE_RADIUS: GridUnit = 4;
+pub const LEVEL_GEN_MIN_WALL: GridUnit = 2;
+pub const LEVEL_GEN_MAX_WALL: GridUnit = 8;
+pub const LEVEL_GEN_MAX_ATTEMPTS: usize = 400;
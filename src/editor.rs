@@ -0,0 +1,405 @@
+use std::time::Instant;
+
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{draw, DrawMode, Font, Mesh, Rect, Scale, StrokeOptions, Text};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use base_types::*;
+use constants::*;
+use grid::*;
+use level::Level;
+use portal::Portal;
+use stone::Stone;
+use theme::Palette;
+use ui::{Button, Center, Message, Stack, Ui, UiEvent};
+use utils::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Tool {
+    Stone,
+    Portal,
+    Spawn,
+    Start,
+    Erase,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum EditorAction {
+    Select(Tool),
+    Load,
+    Save,
+    Play,
+}
+
+const ACTIONS: [EditorAction; 8] = [
+    EditorAction::Select(Tool::Stone),
+    EditorAction::Select(Tool::Portal),
+    EditorAction::Select(Tool::Spawn),
+    EditorAction::Select(Tool::Start),
+    EditorAction::Select(Tool::Erase),
+    EditorAction::Load,
+    EditorAction::Save,
+    EditorAction::Play,
+];
+
+impl EditorAction {
+    fn label(self) -> &'static str {
+        match self {
+            EditorAction::Select(Tool::Stone) => "Stone",
+            EditorAction::Select(Tool::Portal) => "Portal",
+            EditorAction::Select(Tool::Spawn) => "Spawn",
+            EditorAction::Select(Tool::Start) => "Start",
+            EditorAction::Select(Tool::Erase) => "Erase",
+            EditorAction::Load => "Load",
+            EditorAction::Save => "Save",
+            EditorAction::Play => "Play",
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            EditorAction::Select(Tool::Stone) => "stone",
+            EditorAction::Select(Tool::Portal) => "portal",
+            EditorAction::Select(Tool::Spawn) => "spawn",
+            EditorAction::Select(Tool::Start) => "start",
+            EditorAction::Select(Tool::Erase) => "erase",
+            EditorAction::Load => "load",
+            EditorAction::Save => "save",
+            EditorAction::Play => "play",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        ACTIONS.iter().cloned().find(|action| action.id() == id)
+    }
+}
+
+fn create_toolbar(grid: GridVector, font: Font) -> Ui {
+    let mut buttons = Stack::horizontal(8.0);
+    for action in &ACTIONS {
+        let button = Button::new(
+            action.label(),
+            8.0 + FONT_DEFAULT_SIZE,
+            font,
+            Rect::new(0.0, 0.0, 104.0, INFO_BAR_HIGHT - 8.0),
+        );
+        buttons.push(button.with_id(action.id()));
+    }
+
+    Ui::new(
+        Center::new(buttons),
+        Rect::new(0.0, 4.0, window_w(grid), INFO_BAR_HIGHT - 8.0),
+    )
+}
+
+fn draw_cell(ctx: &mut Context, palette: Palette, mode: DrawMode, rect: Rect) -> GameResult<()> {
+    let rectangle = Mesh::new_rectangle(ctx, mode, rect, palette.foreground)?;
+    draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))
+}
+
+fn draw_portal(ctx: &mut Context, palette: Palette, pos: GridVector) -> GameResult<()> {
+    let rect = gv_to_rect(&pos);
+    let circle = Mesh::new_circle(
+        ctx,
+        DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+        na::Point2::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0),
+        rect.w / 2.0 - 1.0,
+        0.1,
+        palette.foreground,
+    )?;
+    draw(ctx, &circle, (na::Point2::new(0.0, 0.0),))
+}
+
+pub struct Editor {
+    pub level: Level,
+    grid: GridVector,
+    tool: Tool,
+    pending_portal: Option<GridVector>,
+    painting: Option<MouseButton>,
+    hovered: Option<GridVector>,
+    toolbar: Ui,
+    font: Font,
+    message: Option<(String, Instant)>,
+    opened_at: Instant,
+    // Edited since the last save or load, and whether closing was asked once already
+    dirty: bool,
+    confirm_close: bool,
+}
+
+impl Editor {
+    pub fn new(grid: GridVector, level: Level, font: Font) -> Self {
+        let mut editor = Editor {
+            level,
+            grid,
+            tool: Tool::Stone,
+            pending_portal: None,
+            painting: None,
+            hovered: None,
+            toolbar: create_toolbar(grid, font),
+            font,
+            message: None,
+            opened_at: Instant::now(),
+            dirty: false,
+            confirm_close: false,
+        };
+        editor.select_tool(Tool::Stone);
+        editor
+    }
+
+    fn select_tool(&mut self, tool: Tool) {
+        self.tool = tool;
+        self.pending_portal = None;
+
+        let selected = EditorAction::Select(tool).id();
+        self.toolbar
+            .visit_mut(&mut |widget| widget.set_highlighted(widget.id() == selected));
+    }
+
+    fn show_message(&mut self, message: String) {
+        self.message = Some((message, Instant::now()));
+    }
+
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        self.confirm_close = false;
+    }
+
+    fn erase(&mut self, pos: GridVector) {
+        let count =
+            |level: &Level| level.stones.len() + level.portals.len() + level.spawn_points.len();
+        let before = (count(&self.level), self.level.start);
+
+        self.level.stones.retain(|stone| stone.pos != pos);
+        self.level
+            .portals
+            .retain(|portal| portal.a != pos && portal.b != pos);
+        self.level.spawn_points.retain(|&spawn| spawn != pos);
+        if self.level.start == Some(pos) {
+            self.level.start = None;
+        }
+        if self.pending_portal == Some(pos) {
+            self.pending_portal = None;
+        }
+
+        if (count(&self.level), self.level.start) != before {
+            self.mark_dirty();
+        }
+    }
+
+    fn paint(&mut self, mb: MouseButton, pos: GridVector) {
+        let tool = if mb == MouseButton::Right {
+            Tool::Erase
+        } else {
+            self.tool
+        };
+
+        match tool {
+            Tool::Stone => {
+                if self.level.stones.iter().all(|stone| stone.pos != pos) {
+                    self.erase(pos);
+                    self.level.stones.push(Stone::new(pos.x, pos.y));
+                    self.mark_dirty();
+                }
+            }
+            Tool::Portal => match self.pending_portal {
+                Some(entry) if entry != pos => {
+                    self.erase(pos);
+                    self.level.portals.push(Portal::new(entry, pos));
+                    self.pending_portal = None;
+                    self.mark_dirty();
+                }
+                _ => {
+                    self.erase(pos);
+                    self.pending_portal = Some(pos);
+                }
+            },
+            // Levels only accept these inside the play area
+            Tool::Spawn => {
+                if in_area(&play_area(self.grid), pos) && !self.level.spawn_points.contains(&pos) {
+                    self.erase(pos);
+                    self.level.spawn_points.push(pos);
+                    self.mark_dirty();
+                }
+            }
+            Tool::Start => {
+                if in_area(&play_area(self.grid), pos) && self.level.start != Some(pos) {
+                    self.erase(pos);
+                    self.level.start = Some(pos);
+                    self.mark_dirty();
+                }
+            }
+            Tool::Erase => self.erase(pos),
+        }
+    }
+
+    fn paints_continuously(&self, mb: MouseButton) -> bool {
+        match self.tool {
+            _ if mb == MouseButton::Right => true,
+            Tool::Stone | Tool::Spawn | Tool::Erase => true,
+            Tool::Portal | Tool::Start => false,
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut Context) -> GameResult<bool> {
+        let mut clicked = None;
+        for message in self.toolbar.poll() {
+            if let Message::Clicked(id) = message {
+                clicked = EditorAction::from_id(id);
+            }
+        }
+
+        match clicked {
+            Some(EditorAction::Select(tool)) => self.select_tool(tool),
+            Some(EditorAction::Load) => match Level::load(ctx, CUSTOM_LEVEL_PATH, self.grid) {
+                Ok(level) => {
+                    self.level = level;
+                    self.pending_portal = None;
+                    self.dirty = false;
+                    self.show_message(format!("Loaded {}", CUSTOM_LEVEL_PATH));
+                }
+                Err(err) => self.show_message(format!("Failed to load level: {}", err)),
+            },
+            Some(EditorAction::Save) => {
+                self.level.name = CUSTOM_LEVEL_NAME.to_string();
+                match self.level.save(ctx, CUSTOM_LEVEL_PATH) {
+                    Ok(()) => {
+                        self.dirty = false;
+                        self.show_message(format!("Saved {}", CUSTOM_LEVEL_PATH));
+                    }
+                    Err(err) => self.show_message(format!("Failed to save level: {}", err)),
+                }
+            }
+            Some(EditorAction::Play) => return Ok(true),
+            None => {}
+        }
+
+        Ok(false)
+    }
+
+    // Unsaved edits need a second request before the editor closes
+    pub fn request_close(&mut self) -> bool {
+        if !self.dirty || self.confirm_close {
+            return true;
+        }
+
+        self.confirm_close = true;
+        self.show_message("Unsaved changes, Save them or press Esc again to discard".to_string());
+        false
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        // Draw play area border
+        {
+            let area = play_area(self.grid);
+            let top_left = gv_to_rect(&area.pos);
+            let bottom_right = gv_to_rect(&(area.pos + area.size));
+            let rect = Rect::new(
+                top_left.x,
+                top_left.y,
+                bottom_right.x - top_left.x,
+                bottom_right.y - top_left.y,
+            );
+            draw_cell(
+                ctx,
+                palette,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+                scale_rect(rect, 2.0),
+            )?;
+        }
+
+        // Draw level
+        for stone in &self.level.stones {
+            draw_cell(ctx, palette, DrawMode::fill(), gv_to_rect(&stone.pos))?;
+        }
+        for portal in &self.level.portals {
+            draw_portal(ctx, palette, portal.a)?;
+            draw_portal(ctx, palette, portal.b)?;
+        }
+        for pos in &self.level.spawn_points {
+            draw_cell(
+                ctx,
+                palette,
+                DrawMode::fill(),
+                scale_rect(gv_to_rect(pos), -5.0),
+            )?;
+        }
+        if let Some(pos) = self.level.start {
+            draw_cell(
+                ctx,
+                palette,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                scale_rect(gv_to_rect(&pos), -1.0),
+            )?;
+        }
+        if let Some(pos) = self.pending_portal {
+            if blinks(since(self.opened_at)) {
+                draw_portal(ctx, palette, pos)?;
+            }
+        }
+
+        // Draw cursor
+        if let Some(pos) = self.hovered {
+            draw_cell(
+                ctx,
+                palette,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+                scale_rect(gv_to_rect(&pos), 1.0),
+            )?;
+        }
+
+        // Draw toolbar
+        self.toolbar.draw(ctx, palette)?;
+
+        // Draw message
+        if let Some((ref message, shown_at)) = self.message {
+            if since(shown_at) < EDITOR_MESSAGE_TIME {
+                let mut text = Text::new(message.as_str());
+                text.set_font(self.font, Scale::uniform(FONT_DEFAULT_SIZE));
+                let dest = na::Point2::new(12.0, window_h(self.grid) - FONT_DEFAULT_SIZE - 12.0);
+                draw(ctx, &text, (dest,))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        self.toolbar.handle(UiEvent::Key(keycode, keymods));
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.toolbar.handle(UiEvent::MouseDown(mb, x, y));
+
+        if y > INFO_BAR_HIGHT {
+            if let Some(pos) = pos_to_gv(self.grid, x, y) {
+                self.painting = Some(mb);
+                self.paint(mb, pos);
+            }
+        }
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.toolbar.handle(UiEvent::MouseUp(mb, x, y));
+
+        if self.painting == Some(mb) {
+            self.painting = None;
+        }
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.toolbar.handle(UiEvent::MouseMotion(x, y));
+
+        self.hovered = if y > INFO_BAR_HIGHT {
+            pos_to_gv(self.grid, x, y)
+        } else {
+            None
+        };
+
+        if let (Some(mb), Some(pos)) = (self.painting, self.hovered) {
+            if self.paints_continuously(mb) {
+                self.paint(mb, pos);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::f32::consts::PI;
+use std::time::Instant;
+
+use ggez::graphics::{draw, DrawMode, Font, Mesh, Rect, Scale, Text, TextFragment};
+use ggez::nalgebra as na;
+use ggez::{timer, Context, GameResult};
+use rand::random;
+
+use base_types::*;
+use constants::*;
+use game_event::GameEvent;
+use theme::Palette;
+use utils::*;
+
+struct Particle {
+    origin: (f32, f32),
+    velocity: (f32, f32),
+    born: Instant,
+}
+
+struct Popup {
+    text: String,
+    origin: (f32, f32),
+    born: Instant,
+}
+
+fn cell_center(pos: GridVector) -> (f32, f32) {
+    let rect = gv_to_rect(&pos);
+    (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0)
+}
+
+pub struct Effects {
+    pub enabled: bool,
+    particles: Vec<Particle>,
+    popups: Vec<Popup>,
+    explosions: Vec<(Instant, GridVector)>,
+    shake_started_at: Option<Instant>,
+    font: Font,
+}
+
+impl Effects {
+    pub fn new(font: Font) -> Self {
+        Effects {
+            enabled: EFFECTS_ENABLED,
+            particles: vec![],
+            popups: vec![],
+            explosions: vec![],
+            shake_started_at: None,
+            font,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.popups.clear();
+        self.explosions.clear();
+        self.shake_started_at = None;
+    }
+
+    fn burst(&mut self, pos: GridVector, count: usize) {
+        let origin = cell_center(pos);
+        for _ in 0..count {
+            let angle = random::<f32>() * PI * 2.0;
+            let speed = PARTICLE_SPEED * (0.5 + random::<f32>() * 0.5);
+            self.particles.push(Particle {
+                origin,
+                velocity: (angle.cos() * speed, angle.sin() * speed),
+                born: Instant::now(),
+            });
+        }
+    }
+
+    fn popup(&mut self, pos: GridVector, text: String) {
+        self.popups.push(Popup {
+            text,
+            origin: cell_center(pos),
+            born: Instant::now(),
+        });
+    }
+
+    fn shake(&mut self) {
+        self.shake_started_at = Some(Instant::now());
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        if !self.enabled {
+            return;
+        }
+
+        match *event {
+            GameEvent::AppleEaten {
+                position, points, ..
+            } => {
+                self.burst(position, PARTICLE_COUNT);
+                self.popup(position, format!("+{}", points));
+            }
+            GameEvent::PowerUpCollected { position, kind } => {
+                self.burst(position, PARTICLE_COUNT);
+                self.popup(position, kind.icon().to_string());
+            }
+            GameEvent::StoneDestroyed { position } | GameEvent::Collision { position } => {
+                self.burst(position, PARTICLE_COUNT);
+                self.shake();
+            }
+            GameEvent::Turned { .. } | GameEvent::BoardFilled | GameEvent::RoundEnded { .. } => {}
+            GameEvent::Died { ref tail, .. } => {
+                let now = Instant::now();
+                for (i, &segment) in tail.iter().rev().enumerate() {
+                    let delay = timer::f64_to_duration(DEATH_EXPLOSION_INTERVAL * i as f64);
+                    self.explosions.push((now + delay, segment));
+                }
+            }
+        }
+    }
+
+    pub fn update(&mut self) {
+        let now = Instant::now();
+        let due: Vec<GridVector> = self
+            .explosions
+            .iter()
+            .filter(|&&(at, _)| at <= now)
+            .map(|&(_, pos)| pos)
+            .collect();
+        self.explosions.retain(|&(at, _)| at > now);
+        for pos in due {
+            self.burst(pos, DEATH_PARTICLE_COUNT);
+        }
+
+        self.particles
+            .retain(|particle| since(particle.born) < PARTICLE_LIFETIME);
+        self.popups
+            .retain(|popup| since(popup.born) < POPUP_LIFETIME);
+
+        let shaking = match self.shake_started_at {
+            Some(started_at) => since(started_at) < SCREEN_SHAKE_TIME,
+            None => false,
+        };
+        if !shaking {
+            self.shake_started_at = None;
+        }
+    }
+
+    pub fn shake_offset(&self) -> (f32, f32) {
+        match self.shake_started_at {
+            Some(started_at) => {
+                let remaining = (1.0 - since(started_at) / SCREEN_SHAKE_TIME).max(0.0) as f32;
+                let strength = SCREEN_SHAKE_STRENGTH * remaining;
+                (
+                    (random::<f32>() * 2.0 - 1.0) * strength,
+                    (random::<f32>() * 2.0 - 1.0) * strength,
+                )
+            }
+            None => (0.0, 0.0),
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        // Draw particles
+        for particle in &self.particles {
+            let t = since(particle.born) as f32;
+            let alpha = 1.0 - t / PARTICLE_LIFETIME as f32;
+            let x = particle.origin.0 + particle.velocity.0 * t;
+            let y = particle.origin.1 + particle.velocity.1 * t + PARTICLE_GRAVITY * t * t / 2.0;
+
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                Rect::new(
+                    x - PARTICLE_SIZE / 2.0,
+                    y - PARTICLE_SIZE / 2.0,
+                    PARTICLE_SIZE,
+                    PARTICLE_SIZE,
+                ),
+                fade(palette.foreground, alpha),
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        // Draw score popups
+        for popup in &self.popups {
+            let t = since(popup.born) / POPUP_LIFETIME;
+            let frag = TextFragment::new(popup.text.as_str())
+                .color(fade(palette.foreground, 1.0 - t as f32));
+            let mut text = Text::new(frag);
+            text.set_font(self.font, Scale::uniform(FONT_DEFAULT_SIZE));
+
+            let dest = na::Point2::new(
+                popup.origin.0 - text.width(ctx) as f32 / 2.0,
+                popup.origin.1 - FONT_DEFAULT_SIZE - POPUP_RISE * t as f32,
+            );
+            draw(ctx, &text, (dest,))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use gif::{self, SetParameter};
+
+use constants::*;
+use grid::{window_h, window_w};
+use render::{Pixmap, Renderer};
+use replay::Replay;
+use theme::Theme;
+
+pub struct ExportOptions {
+    pub scale: u32,
+    // Frames per second of game time, `None` writes one frame per tick
+    pub fps: Option<f64>,
+    pub theme: Theme,
+}
+
+enum Output {
+    Gif(gif::Encoder<BufWriter<File>>),
+    Png(PathBuf),
+}
+
+struct Exporter {
+    output: Output,
+    frames: usize,
+    // Seconds written so far, GIF delays are rounded from it so they don't drift
+    time: f64,
+}
+
+// The palette and the palette index of every pixel, if there are few enough colors
+fn indexed(pixmap: &Pixmap) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut colors = HashMap::new();
+    let mut palette = vec![];
+    let mut indices = Vec::with_capacity(pixmap.pixels.len() / 4);
+    // Most pixels repeat their neighbor, which saves a lookup
+    let mut previous = None;
+    for pixel in pixmap.pixels.chunks(4) {
+        let key = (pixel[0], pixel[1], pixel[2]);
+        if let Some((previous_key, index)) = previous {
+            if previous_key == key {
+                indices.push(index);
+                continue;
+            }
+        }
+        let index = match colors.get(&key) {
+            Some(&index) => index,
+            None if colors.len() < 256 => {
+                let index = colors.len() as u8;
+                colors.insert(key, index);
+                palette.extend_from_slice(&pixel[..3]);
+                index
+            }
+            None => return None,
+        };
+        previous = Some((key, index));
+        indices.push(index);
+    }
+    Some((palette, indices))
+}
+
+impl Exporter {
+    // A path ending in `.gif` becomes an animation, anything else a directory of PNGs
+    fn create(path: &Path, width: u32, height: u32) -> io::Result<Self> {
+        let is_gif = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) => extension.to_lowercase() == "gif",
+            None => false,
+        };
+
+        let output = if is_gif {
+            if width > 0xffff || height > 0xffff {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the image is too large for a GIF",
+                ));
+            }
+            let file = BufWriter::new(File::create(path)?);
+            let mut encoder = gif::Encoder::new(file, width as u16, height as u16, &[])?;
+            encoder.set(gif::Repeat::Infinite)?;
+            Output::Gif(encoder)
+        } else {
+            fs::create_dir_all(path)?;
+            Output::Png(path.to_path_buf())
+        };
+
+        Ok(Exporter {
+            output,
+            frames: 0,
+            time: 0.0,
+        })
+    }
+
+    // Shows the image for `duration` seconds, a PNG sequence repeats it `copies` times
+    fn add(&mut self, pixmap: &Pixmap, copies: usize, duration: f64) -> io::Result<()> {
+        let start = (self.time * 100.0).round();
+        self.time += duration;
+        let delay = (self.time * 100.0).round() - start;
+
+        match self.output {
+            Output::Gif(ref mut encoder) => {
+                let (width, height) = (pixmap.width as u16, pixmap.height as u16);
+                let mut frame = match indexed(pixmap) {
+                    Some((palette, indices)) => {
+                        gif::Frame::from_palette_pixels(width, height, &indices, &palette, None)
+                    }
+                    None => {
+                        let mut pixels = pixmap.pixels.clone();
+                        gif::Frame::from_rgba_speed(width, height, &mut pixels, 10)
+                    }
+                };
+                frame.delay = delay.min(f64::from(0xffff)) as u16;
+                encoder.write_frame(&frame)?;
+                self.frames += 1;
+            }
+            Output::Png(ref directory) => {
+                for _ in 0..copies {
+                    self.frames += 1;
+                    pixmap.save_png(directory.join(format!("frame-{:05}.png", self.frames)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Renders the replay tick by tick and returns the number of frames written
+pub fn export(replay: &Replay, path: &Path, options: &ExportOptions) -> io::Result<usize> {
+    let renderer = Renderer::new(options.theme.palette(None));
+    let mut exporter = Exporter::create(
+        path,
+        window_w(replay.grid) as u32 * options.scale,
+        window_h(replay.grid) as u32 * options.scale,
+    )?;
+
+    let mut game = replay.start();
+    let mut stalled = false;
+    let mut samples = 0;
+    loop {
+        let pixmap = renderer.render(&game, "").scaled(options.scale);
+        let finished = stalled || game.tick >= replay.ticks || game.is_over();
+        let start = game.time;
+        if !finished {
+            game.step(&replay.directions(game.tick));
+            stalled = !game.is_started();
+        }
+        // The last frame stays a little longer so the end is visible
+        let end = if finished {
+            start + EXPORT_FINAL_HOLD
+        } else {
+            game.time
+        };
+
+        match options.fps {
+            Some(fps) => {
+                // Every sample point until the next tick shows this frame
+                let mut copies = 0;
+                while samples as f64 / fps < end {
+                    samples += 1;
+                    copies += 1;
+                }
+                if copies > 0 {
+                    exporter.add(&pixmap, copies, copies as f64 / fps)?;
+                }
+            }
+            None => exporter.add(&pixmap, 1, end - start)?,
+        }
+
+        if finished {
+            return Ok(exporter.frames);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use ggez::graphics::{draw, Color, DrawMode, Font, Mesh, Scale, StrokeOptions, Text, TextFragment};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use arena::Arena;
+use base_types::*;
+use constants::*;
+use game::Game;
+use theme::Palette;
+use utils::*;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FrameSnake {
+    pub name: String,
+    pub score: u32,
+    pub alive: bool,
+    pub tail: Vec<GridVector>,
+}
+
+// A snapshot of everything visible on the board, as streamed to spectators
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame {
+    pub grid: GridVector,
+    pub tick: u64,
+    pub snakes: Vec<FrameSnake>,
+    pub apple: GridVector,
+    pub power_up: Option<GridVector>,
+    pub stones: Vec<GridVector>,
+    pub portals: Vec<(GridVector, GridVector)>,
+}
+
+fn parse_cell(value: &str) -> Option<GridVector> {
+    let mut parts = value.splitn(2, ',');
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    Some(GridVector::new(x, y))
+}
+
+fn parse_cells(values: &[&str]) -> Option<Vec<GridVector>> {
+    values
+        .chunks(2)
+        .map(|pair| match *pair {
+            [x, y] => Some(GridVector::new(x.parse().ok()?, y.parse().ok()?)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn text(content: &str, color: Color, font: Font) -> Text {
+    let mut text = Text::new(TextFragment::new(content).color(color));
+    text.set_font(font, Scale::uniform(FONT_DEFAULT_SIZE));
+    text
+}
+
+impl Frame {
+    pub fn from_game(game: &Game, name: &str) -> Self {
+        let score = game.snake.score;
+        Frame {
+            grid: game.grid,
+            tick: game.tick,
+            snakes: vec![FrameSnake {
+                name: name.to_string(),
+                score,
+                alive: !game.is_over(),
+                tail: game.snake.tail.clone(),
+            }],
+            apple: game.apple.position,
+            power_up: game.power_up.as_ref().map(|power_up| power_up.position),
+            stones: game
+                .stones
+                .iter()
+                .filter(|stone| stone.is_active(game.time, score))
+                .map(|stone| stone.pos)
+                .collect(),
+            portals: game
+                .level
+                .portals
+                .iter()
+                .map(|portal| (portal.a, portal.b))
+                .collect(),
+        }
+    }
+
+    pub fn from_arena(arena: &Arena) -> Self {
+        Frame {
+            grid: arena.grid,
+            tick: arena.tick,
+            snakes: arena
+                .players
+                .iter()
+                .map(|player| FrameSnake {
+                    name: player.name.clone(),
+                    score: player.snake.score,
+                    alive: player.is_alive(),
+                    tail: player.snake.tail.clone(),
+                })
+                .collect(),
+            apple: arena.apple.position,
+            power_up: None,
+            stones: arena
+                .level
+                .stones
+                .iter()
+                .filter(|stone| stone.is_active(arena.time, 0))
+                .map(|stone| stone.pos)
+                .collect(),
+            portals: arena
+                .level
+                .portals
+                .iter()
+                .map(|portal| (portal.a, portal.b))
+                .collect(),
+        }
+    }
+
+    // One command per line, closed by an `end` line
+    pub fn to_source(&self) -> String {
+        let mut source = format!(
+            "frame {}\ngrid {} {}\n",
+            self.tick, self.grid.x, self.grid.y
+        );
+        source += &format!("apple {} {}\n", self.apple.x, self.apple.y);
+        if let Some(pos) = self.power_up {
+            source += &format!("power-up {} {}\n", pos.x, pos.y);
+        }
+        for pos in &self.stones {
+            source += &format!("stone {} {}\n", pos.x, pos.y);
+        }
+        for (a, b) in &self.portals {
+            source += &format!("portal {} {} {} {}\n", a.x, a.y, b.x, b.y);
+        }
+        for snake in &self.snakes {
+            let cells: Vec<String> = snake
+                .tail
+                .iter()
+                .map(|pos| format!("{},{}", pos.x, pos.y))
+                .collect();
+            source += &format!(
+                "snake {} {} {} {}\n",
+                snake.score,
+                if snake.alive { 1 } else { 0 },
+                cells.join(";"),
+                snake.name
+            );
+        }
+        source += "end\n";
+        source
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut frame = Frame {
+            grid: DEFAULT_GRID_SIZE,
+            tick: 0,
+            snakes: vec![],
+            apple: GridVector::new(0, 0),
+            power_up: None,
+            stones: vec![],
+            portals: vec![],
+        };
+
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line == "end" {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, ' ');
+            let command = parts.next().unwrap_or("");
+            let rest = parts.next().unwrap_or("");
+            let args: Vec<&str> = rest.split(' ').collect();
+            let invalid = || format!("invalid line `{}`", line);
+            match command {
+                "frame" => frame.tick = rest.parse().map_err(|_| invalid())?,
+                "grid" => {
+                    let grid = parse_cells(&args)
+                        .and_then(|mut cells| cells.pop())
+                        .ok_or_else(invalid)?;
+                    let valid = MIN_GRID_SIZE..=MAX_GRID_SIZE;
+                    if !valid.contains(&grid.x) || !valid.contains(&grid.y) {
+                        return Err(format!("grid size out of range in `{}`", line));
+                    }
+                    frame.grid = grid;
+                }
+                "apple" => {
+                    frame.apple = parse_cells(&args)
+                        .and_then(|mut cells| cells.pop())
+                        .ok_or_else(invalid)?
+                }
+                "power-up" => frame.power_up = parse_cells(&args).and_then(|mut cells| cells.pop()),
+                "stone" => frame.stones.extend(parse_cells(&args).ok_or_else(invalid)?),
+                "portal" => match parse_cells(&args) {
+                    Some(ref cells) if cells.len() == 2 => frame.portals.push((cells[0], cells[1])),
+                    _ => return Err(invalid()),
+                },
+                "snake" => {
+                    let parts: Vec<&str> = rest.splitn(4, ' ').collect();
+                    if parts.len() != 4 {
+                        return Err(invalid());
+                    }
+                    let tail = parts[2]
+                        .split(';')
+                        .map(parse_cell)
+                        .collect::<Option<Vec<GridVector>>>()
+                        .ok_or_else(invalid)?;
+                    frame.snakes.push(FrameSnake {
+                        name: parts[3].to_string(),
+                        score: parts[0].parse().map_err(|_| invalid())?,
+                        alive: parts[1] == "1",
+                        tail,
+                    });
+                }
+                _ => return Err(format!("unknown command `{}`", command)),
+            }
+        }
+
+        Ok(frame)
+    }
+
+    // Draws the board and the scores, `local` marks the viewer's own snake
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        palette: Palette,
+        font: Font,
+        local: Option<usize>,
+    ) -> GameResult<()> {
+        // Draw stones
+        for pos in &self.stones {
+            let rectangle =
+                Mesh::new_rectangle(ctx, DrawMode::fill(), gv_to_rect(pos), palette.foreground)?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        // Draw portals
+        for &(a, b) in &self.portals {
+            for pos in &[a, b] {
+                let rect = gv_to_rect(pos);
+                let circle = Mesh::new_circle(
+                    ctx,
+                    DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                    na::Point2::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0),
+                    rect.w / 2.0 - 1.0,
+                    0.1,
+                    palette.foreground,
+                )?;
+                draw(ctx, &circle, (na::Point2::new(0.0, 0.0),))?;
+            }
+        }
+
+        // Draw apple
+        let rectangle = Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            gv_to_rect(&self.apple),
+            palette.foreground,
+        )?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        // Draw power-up
+        if let Some(pos) = self.power_up {
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                scale_rect(gv_to_rect(&pos), -1.0),
+                palette.foreground,
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        // Draw snakes
+        for (id, snake) in self.snakes.iter().enumerate() {
+            let color = if snake.alive {
+                palette.player(id)
+            } else {
+                fade(palette.player(id), 0.25)
+            };
+            for segment in &snake.tail {
+                let rectangle =
+                    Mesh::new_rectangle(ctx, DrawMode::fill(), gv_to_rect(segment), color)?;
+                draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+            }
+            if let Some(head) = snake.tail.last() {
+                let rectangle = Mesh::new_rectangle(
+                    ctx,
+                    DrawMode::fill(),
+                    scale_rect(gv_to_rect(head), -2.0),
+                    palette.background,
+                )?;
+                draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+            }
+        }
+
+        // Draw scores
+        let mut x = 12.0;
+        for (id, snake) in self.snakes.iter().enumerate() {
+            let mut label = format!("{}: {}", snake.name, snake.score);
+            if Some(id) == local {
+                label += " (you)";
+            }
+            let text = text(&label, palette.player(id), font);
+            let dest = na::Point2::new(x, (INFO_BAR_HIGHT - FONT_DEFAULT_SIZE) / 2.0 - 4.0);
+            draw(ctx, &text, (dest,))?;
+            x += text.width(ctx) as f32 + 30.0;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,456 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use apple::Apple;
+use base_types::*;
+use constants::*;
+use game_event::{DeathCause, GameEvent};
+use game_mode::{GameConfig, GameMode};
+use grid::*;
+use level::Level;
+use pathfinding::{step, DistanceMap, DIRECTIONS};
+use portal::Portal;
+use power_up::{PowerUp, PowerUpKind};
+use snake::Snake;
+use stone::Stone;
+use utils::*;
+
+pub struct Game {
+    pub grid: GridVector,
+    pub snake: Snake,
+    pub apple: Apple,
+    pub power_up: Option<PowerUp>,
+    pub stones: Vec<Stone>,
+    pub level: Level,
+    pub mode: GameMode,
+    pub config: GameConfig,
+    pub seed: u64,
+    pub initial_speed: f64,
+    pub tick: u64,
+    pub time: f64,
+    pub turns: Vec<(u64, GridVector)>,
+    pub events: Vec<GameEvent>,
+    board_filled: bool,
+    round_ended: bool,
+    rng: StdRng,
+}
+
+// Returns None once the conflicts cover the whole area
+fn random_free_pos<R: Rng>(
+    rng: &mut R,
+    area: &GridArea,
+    conflicts: &[GridVector],
+) -> Option<GridVector> {
+    let width = area.size.x as usize;
+    let mut taken = vec![false; width * area.size.y as usize];
+    for &pos in conflicts.iter().filter(|&&pos| in_area(area, pos)) {
+        let offset = pos - area.pos;
+        taken[offset.y as usize * width + offset.x as usize] = true;
+    }
+
+    let free: Vec<GridVector> = taken
+        .iter()
+        .enumerate()
+        .filter(|&(_, &taken)| !taken)
+        .map(|(index, _)| {
+            area.pos + GridVector::new((index % width) as GridUnit, (index / width) as GridUnit)
+        })
+        .collect();
+    if free.is_empty() {
+        None
+    } else {
+        Some(free[rng.gen::<usize>() % free.len()])
+    }
+}
+
+pub fn spawn_apple<R: Rng>(
+    rng: &mut R,
+    grid: GridVector,
+    level: &Level,
+    conflicts: &[GridVector],
+    time: f64,
+) -> Option<Apple> {
+    let free_spawn_points: Vec<GridVector> = level
+        .spawn_points
+        .iter()
+        .cloned()
+        .filter(|pos| !conflicts.contains(pos))
+        .collect();
+
+    if free_spawn_points.is_empty() {
+        random_free_pos(rng, &play_area(grid), conflicts).map(|pos| Apple::new(pos, time))
+    } else {
+        let index = rng.gen::<usize>() % free_spawn_points.len();
+        Some(Apple::new(free_spawn_points[index], time))
+    }
+}
+
+fn spawn_power_up_in_area<R: Rng>(
+    rng: &mut R,
+    area: &GridArea,
+    conflicts: &[GridVector],
+    time: f64,
+) -> Option<PowerUp> {
+    let kind = PowerUpKind::random(rng);
+    random_free_pos(rng, area, conflicts).map(|pos| PowerUp::new(kind, pos, time))
+}
+
+fn level_stones(level: &Level, config: &GameConfig) -> Vec<Stone> {
+    if config.obstacles {
+        level.stones.clone()
+    } else {
+        vec![]
+    }
+}
+
+fn occupied_cells(snake: &Snake, stones: &[Stone], portals: &[Portal]) -> Vec<GridVector> {
+    let mut cells = snake.tail.clone();
+    cells.extend(stones.iter().map(|stone| stone.pos));
+    for portal in portals {
+        cells.push(portal.a);
+        cells.push(portal.b);
+    }
+    cells
+}
+
+fn fills_area(area: &GridArea, snake: &Snake, stones: &[Stone], portals: &[Portal]) -> bool {
+    let cells = area.size.x as usize * area.size.y as usize;
+    let occupied = occupied_cells(snake, stones, portals);
+    if occupied.len() < cells {
+        return false;
+    }
+
+    let mut inside: Vec<GridVector> = occupied
+        .into_iter()
+        .filter(|&pos| in_area(area, pos))
+        .collect();
+    inside.sort_by_key(|pos| (pos.x, pos.y));
+    inside.dedup();
+    inside.len() >= cells
+}
+
+fn pull_apple(
+    grid: GridVector,
+    apple: &mut Apple,
+    head: GridVector,
+    blocked: &[GridVector],
+    conflicts: &[GridVector],
+    portals: &[Portal],
+) {
+    let distances = DistanceMap::new(grid, head, blocked, portals);
+    let current = match distances.get(apple.position) {
+        Some(distance) if distance <= MAGNET_RANGE => distance,
+        _ => return,
+    };
+
+    let mut closest = None;
+    for direction in &DIRECTIONS {
+        let pos = apple.position + *direction;
+        if !in_area(&play_area(grid), pos) || conflicts.contains(&pos) {
+            continue;
+        }
+
+        if let Some(distance) = distances.get(pos) {
+            let is_closer = match closest {
+                Some((_, other)) => distance < other,
+                None => distance < current,
+            };
+            if is_closer {
+                closest = Some((pos, distance));
+            }
+        }
+    }
+
+    if let Some((pos, _)) = closest {
+        apple.position = pos;
+    }
+}
+
+impl Game {
+    pub fn new(
+        grid: GridVector,
+        level: Level,
+        mode: GameMode,
+        initial_speed: f64,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let config = mode.config();
+
+        let mut snake = match level.start {
+            Some(start) => Snake::at(start),
+            None => Snake::new(&mut rng, grid),
+        };
+        snake.speed = initial_speed;
+        let stones = level_stones(&level, &config);
+        let conflicts = occupied_cells(&snake, &stones, &level.portals);
+        // Only a level without a single free cell leaves no room for the apple
+        let apple = spawn_apple(&mut rng, grid, &level, &conflicts, 0.0)
+            .unwrap_or_else(|| Apple::new(snake.head(), 0.0));
+
+        Game {
+            grid,
+            snake,
+            apple,
+            power_up: None,
+            stones,
+            level,
+            mode,
+            config,
+            seed,
+            initial_speed,
+            tick: 0,
+            time: 0.0,
+            turns: vec![],
+            events: vec![],
+            board_filled: false,
+            round_ended: false,
+            rng,
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.snake.velocity != (GridVector { x: 0, y: 0 })
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.snake.lost_at.is_some()
+    }
+
+    pub fn step(&mut self, directions: &[GridVector]) {
+        if self.is_over() {
+            return;
+        }
+
+        let time = self.time;
+        let deadly = self.config.deadly_collisions;
+        let snake = &mut self.snake;
+
+        let mut new_velocity = None;
+        for &direction in directions {
+            if direction + snake.velocity != (GridVector { x: 0, y: 0 }) {
+                new_velocity = Some(direction);
+            }
+        }
+        match new_velocity {
+            Some(velocity) if velocity != snake.velocity => {
+                self.events.push(GameEvent::Turned {
+                    from: snake.velocity,
+                    to: velocity,
+                });
+                snake.velocity = velocity;
+                self.turns.push((self.tick, velocity));
+            }
+            _ => {}
+        }
+
+        if snake.velocity == (GridVector { x: 0, y: 0 }) {
+            return;
+        }
+
+        snake.expire_power_ups(time);
+
+        let mut cause = None;
+        let mut filled_up = false;
+
+        let time_up = self.config.time_limit.map_or(false, |limit| time > limit);
+        if time_up {
+            cause = Some(DeathCause::TimeUp);
+        } else {
+            for stone in &mut self.stones {
+                stone.update(time);
+            }
+
+            // A stone that moves, blinks or spawns onto the body hits the snake too
+            let crushed = self.stones.iter().position(|stone| {
+                stone.is_active(time, snake.score) && snake.tail.contains(&stone.pos)
+            });
+            if let Some(index) = crushed {
+                let position = self.stones[index].pos;
+                if snake.has_power_up(PowerUpKind::Shield) {
+                    snake.consume_power_up(PowerUpKind::Shield);
+                    self.stones.remove(index);
+                    self.events.push(GameEvent::StoneDestroyed { position });
+                } else if deadly {
+                    cause = Some(DeathCause::Stone);
+                    self.events.push(GameEvent::Collision { position });
+                }
+            }
+
+            let new_head = step(self.grid, snake.head(), snake.velocity, &self.level.portals);
+
+            let ghost = snake.has_power_up(PowerUpKind::Ghost);
+            if deadly && !ghost && snake.tail.contains(&new_head) {
+                cause = Some(DeathCause::SelfCollision);
+                self.events
+                    .push(GameEvent::Collision { position: new_head });
+            }
+
+            let hit_stone = self
+                .stones
+                .iter()
+                .position(|stone| stone.pos == new_head && stone.is_active(time, snake.score));
+            if let Some(index) = hit_stone {
+                if snake.has_power_up(PowerUpKind::Shield) {
+                    snake.consume_power_up(PowerUpKind::Shield);
+                    self.stones.remove(index);
+                    self.events
+                        .push(GameEvent::StoneDestroyed { position: new_head });
+                } else if deadly {
+                    cause = Some(DeathCause::Stone);
+                    self.events
+                        .push(GameEvent::Collision { position: new_head });
+                }
+            }
+
+            if new_head == self.apple.position {
+                let mut conflicts = occupied_cells(snake, &self.stones, &self.level.portals);
+                conflicts.push(new_head);
+                if let Some(ref power_up) = self.power_up {
+                    conflicts.push(power_up.position);
+                }
+                match spawn_apple(&mut self.rng, self.grid, &self.level, &conflicts, time) {
+                    Some(apple) => self.apple = apple,
+                    // The snake covers the whole board, which ends the round
+                    None => filled_up = true,
+                }
+                snake.speed -= snake.speed / SPEED_INCREASE_FRACTION;
+                let points = if snake.has_power_up(PowerUpKind::Multiplier) {
+                    SCORE_MULTIPLIER
+                } else {
+                    1
+                };
+                snake.score += points;
+                self.events.push(GameEvent::AppleEaten {
+                    position: new_head,
+                    points,
+                    length: snake.tail.len(),
+                });
+                snake.grow += GROW_PER_APPLE;
+
+                if self.power_up.is_none() && self.rng.gen::<f64>() < POWER_UP_SPAWN_CHANCE {
+                    let mut conflicts = occupied_cells(snake, &self.stones, &self.level.portals);
+                    conflicts.push(self.apple.position);
+                    self.power_up = spawn_power_up_in_area(
+                        &mut self.rng,
+                        &play_area(self.grid),
+                        &conflicts,
+                        time,
+                    );
+                }
+            }
+
+            let picked_up = match self.power_up {
+                Some(ref power_up) if power_up.position == new_head => Some(power_up.kind),
+                _ => None,
+            };
+            if let Some(kind) = picked_up {
+                snake.activate_power_up(kind, time);
+                self.power_up = None;
+                self.events.push(GameEvent::PowerUpCollected {
+                    position: new_head,
+                    kind,
+                });
+            }
+
+            if cause.is_none() {
+                snake.previous_tail = snake.tail.clone();
+                if snake.grow > 0 {
+                    snake.grow -= 1;
+                } else {
+                    snake.tail.remove(0);
+                }
+                snake.tail.push(new_head);
+
+                if !self.board_filled
+                    && fills_area(
+                        &play_area(self.grid),
+                        snake,
+                        &self.stones,
+                        &self.level.portals,
+                    )
+                {
+                    self.board_filled = true;
+                    self.events.push(GameEvent::BoardFilled);
+                }
+            }
+
+            if let Some(lifetime) = self.config.apple_lifetime {
+                if time - self.apple.spawned_at > lifetime {
+                    let shrink = self.config.shrink_per_missed_apple as usize;
+                    if snake.tail.len() > shrink {
+                        snake.tail.drain(..shrink);
+                    } else if deadly {
+                        cause = Some(DeathCause::Starved);
+                    }
+                    let mut conflicts = occupied_cells(snake, &self.stones, &self.level.portals);
+                    if let Some(ref power_up) = self.power_up {
+                        conflicts.push(power_up.position);
+                    }
+                    // Without a free cell the apple stays where it is
+                    let apple =
+                        spawn_apple(&mut self.rng, self.grid, &self.level, &conflicts, time);
+                    let position = self.apple.position;
+                    self.apple = apple.unwrap_or_else(|| Apple::new(position, time));
+                }
+            }
+
+            if snake.has_power_up(PowerUpKind::Magnet) {
+                let blocked = occupied_cells(snake, &self.stones, &[]);
+                let mut conflicts = occupied_cells(snake, &self.stones, &self.level.portals);
+                if let Some(ref power_up) = self.power_up {
+                    conflicts.push(power_up.position);
+                }
+                pull_apple(
+                    self.grid,
+                    &mut self.apple,
+                    snake.head(),
+                    &blocked,
+                    &conflicts,
+                    &self.level.portals,
+                );
+            }
+        }
+
+        let expired = match self.power_up {
+            Some(ref power_up) => time - power_up.spawned_at > POWER_UP_LIFETIME,
+            None => false,
+        };
+        if expired {
+            self.power_up = None;
+        }
+
+        if filled_up && cause.is_none() {
+            snake.lost_at = Some(time);
+        }
+        if let Some(cause) = cause {
+            snake.lost_at = Some(time);
+            self.events.push(GameEvent::Died {
+                tail: snake.tail.clone(),
+                cause,
+                score: snake.score,
+                time,
+            });
+        }
+
+        self.tick += 1;
+        self.time += snake.speed;
+
+        if self.is_over() {
+            self.end_round();
+        }
+    }
+
+    // Emits RoundEnded once for a started round
+    pub fn end_round(&mut self) {
+        if self.round_ended || !self.is_started() {
+            return;
+        }
+        self.round_ended = true;
+        self.events.push(GameEvent::RoundEnded {
+            length: self.snake.tail.len(),
+            score: self.snake.score,
+            time: self.snake.lost_at.unwrap_or(self.time),
+        });
+    }
+}
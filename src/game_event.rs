@@ -0,0 +1,79 @@
+use base_types::*;
+use power_up::PowerUpKind;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DeathCause {
+    SelfCollision,
+    OtherSnake,
+    Stone,
+    TimeUp,
+    Starved,
+}
+
+impl DeathCause {
+    pub fn all() -> [DeathCause; 5] {
+        [
+            DeathCause::SelfCollision,
+            DeathCause::OtherSnake,
+            DeathCause::Stone,
+            DeathCause::TimeUp,
+            DeathCause::Starved,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DeathCause::SelfCollision => "Bit itself",
+            DeathCause::OtherSnake => "Hit another snake",
+            DeathCause::Stone => "Hit a stone",
+            DeathCause::TimeUp => "Ran out of time",
+            DeathCause::Starved => "Starved",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            DeathCause::SelfCollision => "self",
+            DeathCause::OtherSnake => "snake",
+            DeathCause::Stone => "stone",
+            DeathCause::TimeUp => "time-up",
+            DeathCause::Starved => "starved",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    AppleEaten {
+        position: GridVector,
+        points: u32,
+        length: usize,
+    },
+    PowerUpCollected {
+        position: GridVector,
+        kind: PowerUpKind,
+    },
+    StoneDestroyed {
+        position: GridVector,
+    },
+    Collision {
+        position: GridVector,
+    },
+    Turned {
+        from: GridVector,
+        to: GridVector,
+    },
+    BoardFilled,
+    Died {
+        tail: Vec<GridVector>,
+        cause: DeathCause,
+        score: u32,
+        time: f64,
+    },
+    // Sent once per round, also for a round left before it's over
+    RoundEnded {
+        length: usize,
+        score: u32,
+        time: f64,
+    },
+}
@@ -0,0 +1,97 @@
+use base_types::*;
+use constants::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum GameMode {
+    Endless,
+    TimeAttack,
+    Survival,
+    Zen,
+    Random,
+}
+
+pub struct GameConfig {
+    pub time_limit: Option<f64>,
+    pub apple_lifetime: Option<f64>,
+    pub shrink_per_missed_apple: GridUnit,
+    pub deadly_collisions: bool,
+    pub obstacles: bool,
+    pub random_levels: bool,
+}
+
+impl GameMode {
+    pub fn all() -> [GameMode; 5] {
+        [
+            GameMode::Endless,
+            GameMode::TimeAttack,
+            GameMode::Survival,
+            GameMode::Zen,
+            GameMode::Random,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Endless => "Endless",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Survival => "Survival",
+            GameMode::Zen => "Zen",
+            GameMode::Random => "Random",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            GameMode::Endless => "endless",
+            GameMode::TimeAttack => "time-attack",
+            GameMode::Survival => "survival",
+            GameMode::Zen => "zen",
+            GameMode::Random => "random",
+        }
+    }
+
+    pub fn config(self) -> GameConfig {
+        match self {
+            GameMode::Endless => GameConfig {
+                time_limit: None,
+                apple_lifetime: None,
+                shrink_per_missed_apple: 0,
+                deadly_collisions: true,
+                obstacles: true,
+                random_levels: false,
+            },
+            GameMode::TimeAttack => GameConfig {
+                time_limit: Some(TIME_ATTACK_LIMIT),
+                apple_lifetime: None,
+                shrink_per_missed_apple: 0,
+                deadly_collisions: true,
+                obstacles: true,
+                random_levels: false,
+            },
+            GameMode::Survival => GameConfig {
+                time_limit: None,
+                apple_lifetime: Some(SURVIVAL_APPLE_LIFETIME),
+                shrink_per_missed_apple: SURVIVAL_SHRINK_PER_MISSED_APPLE,
+                deadly_collisions: true,
+                obstacles: true,
+                random_levels: false,
+            },
+            GameMode::Zen => GameConfig {
+                time_limit: None,
+                apple_lifetime: None,
+                shrink_per_missed_apple: 0,
+                deadly_collisions: false,
+                obstacles: false,
+                random_levels: false,
+            },
+            GameMode::Random => GameConfig {
+                time_limit: None,
+                apple_lifetime: None,
+                shrink_per_missed_apple: 0,
+                deadly_collisions: true,
+                obstacles: true,
+                random_levels: true,
+            },
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use ggez::graphics::Rect;
+
+use base_types::*;
+use constants::*;
+
+pub fn grid_area(size: GridVector) -> GridArea {
+    GridArea {
+        pos: GridVector::new(0, 0),
+        size,
+    }
+}
+
+pub fn play_area(size: GridVector) -> GridArea {
+    GridArea {
+        pos: GridVector::new(2, 2),
+        size: GridVector::new(size.x - 4, size.y - 4),
+    }
+}
+
+pub fn window_w(size: GridVector) -> f32 {
+    f32::from(size.x) * GRID_TILE_SIZE
+}
+
+pub fn window_h(size: GridVector) -> f32 {
+    f32::from(size.y) * GRID_TILE_SIZE + INFO_BAR_HIGHT
+}
+
+// The logical window of a grid, everything is laid out in it
+pub fn window_rect(size: GridVector) -> Rect {
+    Rect::new(0.0, 0.0, window_w(size), window_h(size))
+}
@@ -0,0 +1,66 @@
+use ggez::{filesystem, Context};
+use std::io::Read;
+
+use constants::*;
+use game_mode::GameMode;
+
+pub struct HighScores {
+    pub mode: GameMode,
+    pub scores: Vec<u32>,
+}
+
+fn high_scores_path(mode: GameMode) -> String {
+    format!("/highscores-{}.txt", mode.id())
+}
+
+impl HighScores {
+    pub fn parse(mode: GameMode, source: &str) -> Self {
+        let mut scores: Vec<u32> = source
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+
+        scores.sort_by(|a, b| b.cmp(a));
+        scores.truncate(HIGH_SCORE_TABLE_SIZE);
+
+        HighScores { mode, scores }
+    }
+
+    pub fn to_source(&self) -> String {
+        self.scores
+            .iter()
+            .map(|score| format!("{}\n", score))
+            .collect()
+    }
+
+    pub fn load(ctx: &mut Context, mode: GameMode) -> Self {
+        let mut content = String::new();
+        if let Ok(mut file) = filesystem::open(ctx, high_scores_path(mode)) {
+            if file.read_to_string(&mut content).is_err() {
+                content.clear();
+            }
+        }
+
+        HighScores::parse(mode, &content)
+    }
+
+    pub fn insert(&mut self, score: u32) -> bool {
+        if score == 0 {
+            return false;
+        }
+
+        let index = self
+            .scores
+            .iter()
+            .position(|&other| score > other)
+            .unwrap_or(self.scores.len());
+
+        if index >= HIGH_SCORE_TABLE_SIZE {
+            return false;
+        }
+
+        self.scores.insert(index, score);
+        self.scores.truncate(HIGH_SCORE_TABLE_SIZE);
+        true
+    }
+}
@@ -0,0 +1,173 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use constants::*;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+// Reads one line out of the `remaining` bytes the headers may still take up
+fn read_line<R: BufRead>(reader: &mut R, remaining: &mut usize) -> io::Result<String> {
+    let mut line = String::new();
+    let read = reader
+        .by_ref()
+        .take(*remaining as u64)
+        .read_line(&mut line)?;
+    *remaining -= read;
+    if !line.ends_with('\n') && *remaining == 0 {
+        return Err(invalid("headers too large"));
+    }
+    Ok(line)
+}
+
+// Reads the headers and returns the announced body length
+fn read_headers<R: BufRead>(reader: &mut R, remaining: &mut usize) -> io::Result<usize> {
+    let mut length = 0;
+    loop {
+        let line = read_line(reader, remaining)?;
+        if line.is_empty() {
+            return Err(invalid("connection closed in headers"));
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(length);
+        }
+
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim();
+        let value = parts.next().unwrap_or("").trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            length = value
+                .parse()
+                .map_err(|_| invalid("invalid content length"))?;
+        }
+    }
+}
+
+fn read_body<R: Read>(reader: &mut R, length: usize) -> io::Result<String> {
+    if length > HTTP_MAX_BODY {
+        return Err(invalid("body too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body).map_err(|_| invalid("body is not UTF-8"))
+}
+
+pub fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut remaining = HTTP_MAX_HEADERS;
+    let line = read_line(&mut reader, &mut remaining)?;
+    let mut words = line.split_whitespace();
+    let method = words.next().unwrap_or("").to_string();
+    let target = words
+        .next()
+        .ok_or_else(|| invalid("missing request target"))?;
+
+    let mut parts = target.splitn(2, '?');
+    let path = parts.next().unwrap_or("").to_string();
+    let query = parts
+        .next()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or("").to_string();
+            let value = parts.next().unwrap_or("").to_string();
+            (key, value)
+        })
+        .collect();
+
+    let length = read_headers(&mut reader, &mut remaining)?;
+    let body = read_body(&mut reader, length)?;
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        body,
+    })
+}
+
+pub fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+pub fn send(address: &str, method: &str, target: &str, body: &str) -> io::Result<Response> {
+    let socket = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| invalid("address did not resolve"))?;
+    let mut stream = TcpStream::connect_timeout(&socket, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        target,
+        address,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut remaining = HTTP_MAX_HEADERS;
+    let line = read_line(&mut reader, &mut remaining)?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| invalid("invalid status line"))?;
+
+    let length = read_headers(&mut reader, &mut remaining)?;
+    let body = read_body(&mut reader, length)?;
+
+    Ok(Response { status, body })
+}
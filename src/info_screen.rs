@@ -0,0 +1,121 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{Align, Font, Rect};
+use ggez::{Context, GameResult};
+
+use theme::Palette;
+use ui::{Button, Center, Label, Message, Stack, Ui, UiEvent};
+
+const VALUE_WIDTH: f32 = 110.0;
+const ROWS_PER_COLUMN: usize = 8;
+
+fn build_ui(title: &str, rows: &[(String, String)], name_width: f32, area: Rect, font: Font) -> Ui {
+    let mut columns = Stack::horizontal(30.0);
+    for chunk in rows.chunks(ROWS_PER_COLUMN) {
+        let mut names = Stack::vertical(6.0);
+        let mut values = Stack::vertical(6.0);
+        for (name, value) in chunk {
+            names.push(Label::new(name, font, name_width).aligned(Align::Left));
+            values.push(Label::new(value, font, VALUE_WIDTH).aligned(Align::Right));
+        }
+        columns.push(Stack::horizontal(6.0).with(names).with(values));
+    }
+
+    let back = Button::new(
+        "Back",
+        36.0,
+        font,
+        Rect::new(0.0, 0.0, 2.0 * VALUE_WIDTH, 36.0),
+    );
+    let root = Stack::vertical(16.0)
+        .with(Label::new(title, font, name_width + VALUE_WIDTH))
+        .with(columns)
+        .with(back.with_id("back"));
+
+    Ui::new(Center::new(root), area)
+}
+
+pub struct InfoScreen {
+    pub closed: bool,
+    title: String,
+    rows: Vec<(String, String)>,
+    name_width: f32,
+    ui: Ui,
+    area: Rect,
+    font: Font,
+}
+
+impl InfoScreen {
+    pub fn new(
+        title: &str,
+        rows: Vec<(String, String)>,
+        name_width: f32,
+        area: Rect,
+        font: Font,
+    ) -> Self {
+        let mut ui = build_ui(title, &rows, name_width, area, font);
+        ui.set_focus(Some(0));
+
+        InfoScreen {
+            closed: false,
+            title: title.to_string(),
+            rows,
+            name_width,
+            ui,
+            area,
+            font,
+        }
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<(String, String)>) {
+        self.rows = rows;
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.ui = build_ui(
+            &self.title,
+            &self.rows,
+            self.name_width,
+            self.area,
+            self.font,
+        );
+        self.ui.set_focus(Some(0));
+    }
+
+    pub fn update(&mut self, area: Rect) {
+        if self.area != area {
+            self.area = area;
+            self.rebuild();
+        }
+
+        for message in self.ui.poll() {
+            if let Message::Clicked("back") = message {
+                self.closed = true;
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.ui.draw(ctx, palette)
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        if keycode == KeyCode::Escape {
+            self.closed = true;
+        } else {
+            self.ui.handle(UiEvent::Key(keycode, keymods));
+        }
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseDown(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseUp(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseMotion(x, y));
+    }
+}
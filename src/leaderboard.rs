@@ -0,0 +1,252 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+use constants::*;
+use game_mode::GameMode;
+use http;
+use level::Level;
+use level_gen;
+use replay::Replay;
+use settings::{GridSize, SpeedPreset};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Entry {
+    pub mode: GameMode,
+    pub score: u32,
+    pub name: String,
+}
+
+fn parse_mode(id: &str) -> Option<GameMode> {
+    GameMode::all().iter().cloned().find(|mode| mode.id() == id)
+}
+
+pub fn rows(entries: &[Entry]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            (
+                format!("{}. {}", i + 1, entry.name),
+                entry.score.to_string(),
+            )
+        })
+        .collect()
+}
+
+pub fn submit(server: &str, player: &str, replay: &Replay) -> Result<usize, String> {
+    let body = format!("player {}\n{}", player, replay.to_source());
+    let response = http::send(server, "POST", "/scores", &body).map_err(|err| err.to_string())?;
+
+    if response.status != 200 {
+        return Err(response.body.trim().to_string());
+    }
+
+    let rank = response.body.trim().trim_start_matches("rank").trim();
+    rank.parse()
+        .map_err(|_| format!("unexpected response `{}`", response.body.trim()))
+}
+
+pub fn fetch(server: &str, mode: GameMode, limit: usize) -> Result<Vec<Entry>, String> {
+    let target = format!("/scores?mode={}&limit={}", mode.id(), limit);
+    let response = http::send(server, "GET", &target, "").map_err(|err| err.to_string())?;
+
+    if response.status != 200 {
+        return Err(response.body.trim().to_string());
+    }
+
+    Ok(response
+        .body
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ' ');
+            let score = parts.next()?.parse().ok()?;
+            let name = parts.next()?.to_string();
+            Some(Entry { mode, score, name })
+        })
+        .collect())
+}
+
+pub enum Reply {
+    Submitted(Result<usize, String>),
+    Fetched(GameMode, Result<Vec<Entry>, String>),
+}
+
+// Runs requests on background threads so the game loop never waits on the network
+pub struct Client {
+    pub server: String,
+    replies: Vec<Receiver<Reply>>,
+}
+
+impl Client {
+    pub fn new(server: &str) -> Self {
+        Client {
+            server: server.to_string(),
+            replies: vec![],
+        }
+    }
+
+    fn spawn<F: FnOnce(&str) -> Reply + Send + 'static>(&mut self, request: F) {
+        let (sender, receiver) = channel();
+        let server = self.server.clone();
+        thread::spawn(move || {
+            let _ = sender.send(request(&server));
+        });
+        self.replies.push(receiver);
+    }
+
+    pub fn submit(&mut self, player: &str, replay: Replay) {
+        let player = player.to_string();
+        self.spawn(move |server| Reply::Submitted(submit(server, &player, &replay)));
+    }
+
+    pub fn fetch(&mut self, mode: GameMode, limit: usize) {
+        self.spawn(move |server| Reply::Fetched(mode, fetch(server, mode, limit)));
+    }
+
+    pub fn poll(&mut self) -> Vec<Reply> {
+        let mut replies = vec![];
+        self.replies.retain(|receiver| match receiver.try_recv() {
+            Ok(reply) => {
+                replies.push(reply);
+                false
+            }
+            Err(err) => err == ::std::sync::mpsc::TryRecvError::Empty,
+        });
+        replies
+    }
+}
+
+pub struct Board {
+    pub path: PathBuf,
+    pub entries: Vec<Entry>,
+}
+
+impl Board {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let entries = source
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ' ');
+                let mode = parse_mode(parts.next()?)?;
+                let score = parts.next()?.parse().ok()?;
+                let name = parts.next()?.to_string();
+                Some(Entry { mode, score, name })
+            })
+            .collect();
+
+        Ok(Board { path, entries })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let source: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{} {} {}\n", entry.mode.id(), entry.score, entry.name))
+            .collect();
+        fs::write(&self.path, source)
+    }
+
+    pub fn top(&self, mode: GameMode, limit: usize) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.mode == mode)
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| Reverse(entry.score));
+        entries.truncate(limit);
+        entries
+    }
+
+    // Returns the rank of the new entry within its mode
+    pub fn insert(&mut self, entry: Entry) -> io::Result<usize> {
+        let rank = 1 + self
+            .entries
+            .iter()
+            .filter(|other| other.mode == entry.mode && other.score > entry.score)
+            .count();
+        self.entries.push(entry);
+        self.save()?;
+        Ok(rank)
+    }
+}
+
+pub fn parse_submission(body: &str) -> Result<(String, Replay), String> {
+    let mut parts = body.splitn(2, '\n');
+    let first = parts.next().unwrap_or("");
+    if !first.starts_with("player ") {
+        return Err("expected `player NAME` on the first line".to_string());
+    }
+
+    let name = first["player ".len()..].trim().to_string();
+    if name.is_empty() {
+        return Err("missing player name".to_string());
+    }
+    // The board file keeps one entry per line
+    if name.chars().any(char::is_control) {
+        return Err("invalid player name".to_string());
+    }
+
+    let replay = Replay::parse(parts.next().unwrap_or("")).map_err(|err| err.to_string())?;
+    Ok((name, replay))
+}
+
+// Re-simulates the replay and only accepts it if it reproduces the claimed score
+// on a level, speed and grid the game itself offers
+pub fn validate(replay: &Replay) -> Result<Entry, String> {
+    if replay.ticks > MAX_REPLAY_TICKS {
+        return Err(format!(
+            "replays can't be longer than {} ticks",
+            MAX_REPLAY_TICKS
+        ));
+    }
+    let speeds = SpeedPreset::all();
+    if !speeds
+        .iter()
+        .any(|speed| speed.initial_speed() == replay.speed)
+    {
+        return Err("the speed is not one of the presets".to_string());
+    }
+    // Scores on every grid share one board, so only the sizes the settings offer count
+    let sizes = GridSize::all();
+    if !sizes.iter().any(|size| size.size() == replay.grid) {
+        return Err("the grid is not one of the presets".to_string());
+    }
+
+    let source = replay.level.to_source();
+    let known = if replay.mode.config().random_levels {
+        level_gen::generate_for_seed(replay.seed, replay.grid).to_source() == source
+    } else {
+        Level::built_in(replay.grid)
+            .iter()
+            .any(|level| level.to_source() == source)
+    };
+    if !known {
+        return Err("the level is not a built-in level".to_string());
+    }
+
+    let game = replay.simulate();
+    if !replay.matches(&game) {
+        return Err(format!(
+            "replay does not reproduce its score (claimed {}, simulated {})",
+            replay.score, game.snake.score
+        ));
+    }
+
+    Ok(Entry {
+        mode: replay.mode,
+        score: replay.score,
+        name: String::new(),
+    })
+}
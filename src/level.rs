@@ -0,0 +1,392 @@
+use ggez::{filesystem, Context, GameError, GameResult};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use base_types::*;
+use constants::*;
+use grid::*;
+use portal::Portal;
+use stone::{level_corners, Blink, Movement, Stone};
+use utils::in_area;
+
+const BUILT_IN_LEVELS: [(&str, &str); 2] = [
+    (
+        "rising-tide",
+        include_str!("../resources/levels/rising-tide.txt"),
+    ),
+    (
+        "wormholes",
+        include_str!("../resources/levels/wormholes.txt"),
+    ),
+];
+
+#[derive(Clone)]
+pub struct Level {
+    pub name: String,
+    pub stones: Vec<Stone>,
+    pub portals: Vec<Portal>,
+    pub spawn_points: Vec<GridVector>,
+    pub start: Option<GridVector>,
+}
+
+fn parse_error(line_number: usize, message: &str) -> GameError {
+    GameError::ResourceLoadError(format!("line {}: {}", line_number + 1, message))
+}
+
+fn parse_number<T: ::std::str::FromStr>(line_number: usize, value: &str) -> GameResult<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| parse_error(line_number, &format!("invalid number `{}`", value)))
+}
+
+fn parse_pos(line_number: usize, x: &str, y: &str) -> GameResult<GridVector> {
+    Ok(GridVector::new(
+        parse_number(line_number, x)?,
+        parse_number(line_number, y)?,
+    ))
+}
+
+// Positions outside the board would be drawn off screen or break path finding
+fn parse_pos_in(line_number: usize, area: &GridArea, x: &str, y: &str) -> GameResult<GridVector> {
+    let pos = parse_pos(line_number, x, y)?;
+    if !in_area(area, pos) {
+        return Err(parse_error(
+            line_number,
+            &format!("position {},{} is outside the board", pos.x, pos.y),
+        ));
+    }
+    Ok(pos)
+}
+
+fn expand_path(waypoints: &[GridVector]) -> Vec<GridVector> {
+    let mut path = vec![];
+
+    for pair in waypoints.windows(2) {
+        let mut pos = pair[0];
+        let step = GridVector::new((pair[1].x - pos.x).signum(), (pair[1].y - pos.y).signum());
+        while pos != pair[1] {
+            path.push(pos);
+            pos = pos + step;
+        }
+    }
+    if let Some(&last) = waypoints.last() {
+        path.push(last);
+    }
+
+    path
+}
+
+fn parse_point(line_number: usize, grid: GridVector, args: &[&str]) -> GameResult<GridVector> {
+    if args.len() != 2 {
+        return Err(parse_error(line_number, "expected `<x> <y>`"));
+    }
+
+    parse_pos_in(line_number, &play_area(grid), args[0], args[1])
+}
+
+fn format_stone(stone: &Stone) -> String {
+    let start = match stone.movement {
+        Movement::Patrol { ref path, .. } if !path.is_empty() => path[0],
+        _ => stone.pos,
+    };
+    let mut line = format!("stone {} {}", start.x, start.y);
+
+    if let Movement::Patrol { ref path, interval } = stone.movement {
+        if path.len() > 1 {
+            let waypoints: Vec<String> = path[1..]
+                .iter()
+                .map(|pos| format!("{},{}", pos.x, pos.y))
+                .collect();
+            line += &format!(" patrol={} interval={}", waypoints.join(";"), interval);
+        }
+    }
+    if let Some(blink) = stone.blink {
+        line += &format!(" blink={},{}", blink.on, blink.off);
+    }
+    if stone.min_score > 0 {
+        line += &format!(" score={}", stone.min_score);
+    }
+
+    line
+}
+
+fn parse_portal(line_number: usize, grid: GridVector, args: &[&str]) -> GameResult<Portal> {
+    if args.len() != 4 {
+        return Err(parse_error(
+            line_number,
+            "expected `portal <x1> <y1> <x2> <y2>`",
+        ));
+    }
+
+    Ok(Portal::new(
+        parse_pos_in(line_number, &grid_area(grid), args[0], args[1])?,
+        parse_pos_in(line_number, &grid_area(grid), args[2], args[3])?,
+    ))
+}
+
+fn parse_stone(line_number: usize, grid: GridVector, args: &[&str]) -> GameResult<Stone> {
+    if args.len() < 2 {
+        return Err(parse_error(line_number, "expected `stone <x> <y>`"));
+    }
+
+    let pos = parse_pos_in(line_number, &grid_area(grid), args[0], args[1])?;
+    let mut stone = Stone::new(pos.x, pos.y);
+    let mut waypoints = vec![pos];
+    let mut interval = None;
+
+    for option in &args[2..] {
+        let mut parts = option.splitn(2, '=');
+        let key = parts.next().unwrap_or("");
+        let value = parts
+            .next()
+            .ok_or_else(|| parse_error(line_number, &format!("expected `{}=<value>`", key)))?;
+
+        match key {
+            "patrol" => {
+                for waypoint in value.split(';') {
+                    let coords: Vec<&str> = waypoint.split(',').collect();
+                    if coords.len() != 2 {
+                        return Err(parse_error(line_number, "expected `patrol=x,y;x,y`"));
+                    }
+                    waypoints.push(parse_pos_in(
+                        line_number,
+                        &grid_area(grid),
+                        coords[0],
+                        coords[1],
+                    )?);
+                }
+            }
+            "interval" => {
+                let seconds: f64 = parse_number(line_number, value)?;
+                if !seconds.is_finite() || seconds <= 0.0 {
+                    return Err(parse_error(line_number, "interval must be greater than 0"));
+                }
+                interval = Some(seconds);
+            }
+            "blink" => {
+                let times: Vec<&str> = value.split(',').collect();
+                if times.len() != 2 {
+                    return Err(parse_error(line_number, "expected `blink=<on>,<off>`"));
+                }
+                let blink = Blink {
+                    on: parse_number(line_number, times[0])?,
+                    off: parse_number(line_number, times[1])?,
+                };
+                // The blink cycle is `on + off` long, it can't be empty
+                let valid = |time: f64| time.is_finite() && time >= 0.0;
+                if !valid(blink.on) || !valid(blink.off) || blink.on + blink.off <= 0.0 {
+                    return Err(parse_error(
+                        line_number,
+                        "blink times can't be negative or both 0",
+                    ));
+                }
+                stone.blink = Some(blink);
+            }
+            "score" => stone.min_score = parse_number(line_number, value)?,
+            _ => {
+                return Err(parse_error(
+                    line_number,
+                    &format!("unknown stone option `{}`", key),
+                ))
+            }
+        }
+    }
+
+    // The path is walked one cell at a time, so every leg has to be straight
+    if waypoints
+        .windows(2)
+        .any(|pair| pair[0].x != pair[1].x && pair[0].y != pair[1].y)
+    {
+        return Err(parse_error(
+            line_number,
+            "patrol waypoints must be in a straight line from the previous one",
+        ));
+    }
+
+    if waypoints.len() > 1 {
+        stone.movement = Movement::Patrol {
+            path: expand_path(&waypoints),
+            interval: interval.unwrap_or(0.2),
+        };
+    }
+
+    Ok(stone)
+}
+
+impl Level {
+    pub fn corners(grid: GridVector) -> Self {
+        Level {
+            name: "Corners".to_string(),
+            stones: level_corners(grid),
+            portals: vec![],
+            spawn_points: vec![],
+            start: None,
+        }
+    }
+
+    // Fails if anything lies outside a grid of the given size
+    pub fn parse(default_name: &str, source: &str, grid: GridVector) -> GameResult<Self> {
+        let mut level = Level {
+            name: default_name.to_string(),
+            stones: vec![],
+            portals: vec![],
+            spawn_points: vec![],
+            start: None,
+        };
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap_or("");
+            let args: Vec<&str> = words.collect();
+
+            match command {
+                "name" => level.name = args.join(" "),
+                "stone" => level.stones.push(parse_stone(line_number, grid, &args)?),
+                "portal" => level.portals.push(parse_portal(line_number, grid, &args)?),
+                "spawn" => level
+                    .spawn_points
+                    .push(parse_point(line_number, grid, &args)?),
+                "start" => level.start = Some(parse_point(line_number, grid, &args)?),
+                _ => {
+                    return Err(parse_error(
+                        line_number,
+                        &format!("unknown command `{}`", command),
+                    ))
+                }
+            }
+        }
+
+        Ok(level)
+    }
+
+    // The same bounds `parse` checks, for a level parsed for another grid
+    pub fn fits(&self, grid: GridVector) -> bool {
+        let board = grid_area(grid);
+        let play = play_area(grid);
+
+        let stones = self.stones.iter().all(|stone| {
+            let path_fits = match stone.movement {
+                Movement::Patrol { ref path, .. } => path.iter().all(|&pos| in_area(&board, pos)),
+                Movement::Fixed => true,
+            };
+            path_fits && in_area(&board, stone.pos)
+        });
+        let portals = self
+            .portals
+            .iter()
+            .all(|portal| in_area(&board, portal.a) && in_area(&board, portal.b));
+        let points = self
+            .spawn_points
+            .iter()
+            .chain(self.start.iter())
+            .all(|&pos| in_area(&play, pos));
+
+        stones && portals && points
+    }
+
+    pub fn to_source(&self) -> String {
+        let mut lines = vec![format!("name {}", self.name)];
+
+        for stone in &self.stones {
+            lines.push(format_stone(stone));
+        }
+        for portal in &self.portals {
+            lines.push(format!(
+                "portal {} {} {} {}",
+                portal.a.x, portal.a.y, portal.b.x, portal.b.y
+            ));
+        }
+        for pos in &self.spawn_points {
+            lines.push(format!("spawn {} {}", pos.x, pos.y));
+        }
+        if let Some(pos) = self.start {
+            lines.push(format!("start {} {}", pos.x, pos.y));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, ctx: &mut Context, path: P) -> GameResult<()> {
+        let mut file = filesystem::create(ctx, path)?;
+        file.write_all(self.to_source().as_bytes())?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(ctx: &mut Context, path: P, grid: GridVector) -> GameResult<Self> {
+        let path = path.as_ref();
+        let mut source = String::new();
+        filesystem::open(ctx, path)?.read_to_string(&mut source)?;
+
+        Level::parse_file(path, &source, grid)
+    }
+
+    pub fn load_file<P: AsRef<Path>>(path: P, grid: GridVector) -> GameResult<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+
+        Level::parse_file(path, &source, grid)
+    }
+
+    fn parse_file(path: &Path, source: &str, grid: GridVector) -> GameResult<Self> {
+        let default_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Level::parse(&default_name, source, grid).map_err(|err| match err {
+            GameError::ResourceLoadError(message) => {
+                GameError::ResourceLoadError(format!("{}: {}", path.display(), message))
+            }
+            err => err,
+        })
+    }
+
+    // The levels that ship with the game, leaving out those that don't fit the grid
+    pub fn built_in(grid: GridVector) -> Vec<Self> {
+        let largest = GridVector::new(MAX_GRID_SIZE, MAX_GRID_SIZE);
+        let mut levels = vec![Level::corners(grid)];
+        for &(name, source) in &BUILT_IN_LEVELS {
+            match Level::parse(name, source, largest) {
+                Ok(level) => {
+                    if level.fits(grid) {
+                        levels.push(level);
+                    }
+                }
+                Err(err) => eprintln!("Failed to load level {}: {}", name, err),
+            }
+        }
+        levels
+    }
+
+    // Levels that don't fit the grid are left out
+    pub fn load_all(ctx: &mut Context, grid: GridVector) -> Vec<Self> {
+        let mut levels = Level::built_in(grid);
+
+        let mut paths: Vec<_> = match filesystem::read_dir(ctx, "/levels") {
+            Ok(paths) => paths
+                .filter(|path| path.extension().map_or(false, |ext| ext == "txt"))
+                .collect(),
+            Err(_) => vec![],
+        };
+        paths.sort();
+
+        for path in paths {
+            match Level::load(ctx, &path, grid) {
+                Ok(level) => match levels.iter().position(|other| other.name == level.name) {
+                    Some(index) => levels[index] = level,
+                    None => levels.push(level),
+                },
+                Err(err) => eprintln!("Failed to load level: {}", err),
+            }
+        }
+
+        levels
+    }
+}
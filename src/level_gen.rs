@@ -0,0 +1,170 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use base_types::*;
+use constants::*;
+use grid::*;
+use level::Level;
+use pathfinding::DistanceMap;
+use stone::Stone;
+use utils::in_area;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Symmetry {
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+    Rotational,
+}
+
+impl Symmetry {
+    pub fn all() -> [Symmetry; 5] {
+        [
+            Symmetry::None,
+            Symmetry::Horizontal,
+            Symmetry::Vertical,
+            Symmetry::Both,
+            Symmetry::Rotational,
+        ]
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct GeneratorParams {
+    pub density: f32,
+    pub symmetry: Symmetry,
+    pub corridor_width: GridUnit,
+    pub safe_radius: GridUnit,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        GeneratorParams {
+            density: LEVEL_GEN_DENSITY,
+            symmetry: Symmetry::Rotational,
+            corridor_width: LEVEL_GEN_CORRIDOR_WIDTH,
+            safe_radius: LEVEL_GEN_SAFE_RADIUS,
+        }
+    }
+}
+
+fn mirror_x(area: &GridArea, pos: GridVector) -> GridVector {
+    GridVector::new(2 * area.pos.x + area.size.x - 1 - pos.x, pos.y)
+}
+
+fn mirror_y(area: &GridArea, pos: GridVector) -> GridVector {
+    GridVector::new(pos.x, 2 * area.pos.y + area.size.y - 1 - pos.y)
+}
+
+fn mirrored(area: &GridArea, cells: &[GridVector], symmetry: Symmetry) -> Vec<GridVector> {
+    let mut result = cells.to_vec();
+
+    for &cell in cells {
+        match symmetry {
+            Symmetry::None => {}
+            Symmetry::Horizontal => result.push(mirror_x(area, cell)),
+            Symmetry::Vertical => result.push(mirror_y(area, cell)),
+            Symmetry::Both => {
+                result.push(mirror_x(area, cell));
+                result.push(mirror_y(area, cell));
+                result.push(mirror_x(area, mirror_y(area, cell)));
+            }
+            Symmetry::Rotational => result.push(mirror_x(area, mirror_y(area, cell))),
+        }
+    }
+
+    let mut unique = vec![];
+    for cell in result {
+        if !unique.contains(&cell) {
+            unique.push(cell);
+        }
+    }
+    unique
+}
+
+fn random_wall(rng: &mut StdRng, area: &GridArea) -> Vec<GridVector> {
+    let length = rng.gen_range(LEVEL_GEN_MIN_WALL, LEVEL_GEN_MAX_WALL + 1);
+    let direction = if rng.gen() {
+        GridVector::new(1, 0)
+    } else {
+        GridVector::new(0, 1)
+    };
+    let start = GridVector::new(
+        rng.gen_range(area.pos.x, area.pos.x + area.size.x),
+        rng.gen_range(area.pos.y, area.pos.y + area.size.y),
+    );
+
+    (0..length)
+        .map(|i| GridVector::new(start.x + direction.x * i, start.y + direction.y * i))
+        .collect()
+}
+
+fn chebyshev_distance(a: GridVector, b: GridVector) -> GridUnit {
+    (a.x - b.x).abs().max((a.y - b.y).abs())
+}
+
+fn all_reachable(grid: GridVector, start: GridVector, stones: &[GridVector]) -> bool {
+    let distances = DistanceMap::new(grid, start, stones, &[]);
+
+    for y in 0..grid.y {
+        for x in 0..grid.x {
+            let pos = GridVector::new(x, y);
+            if !stones.contains(&pos) && distances.get(pos).is_none() {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+pub fn generate(seed: u64, grid: GridVector, params: &GeneratorParams) -> Level {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let area = play_area(grid);
+    let start = area.pos + GridVector::new(area.size.x / 2, area.size.y / 2);
+    let target = (f32::from(area.size.x) * f32::from(area.size.y) * params.density) as usize;
+    let mut stones: Vec<GridVector> = vec![];
+
+    for _ in 0..LEVEL_GEN_MAX_ATTEMPTS {
+        if stones.len() >= target {
+            break;
+        }
+
+        let wall = mirrored(&area, &random_wall(&mut rng, &area), params.symmetry);
+        let fits = wall.iter().all(|&cell| {
+            in_area(&area, cell)
+                && chebyshev_distance(cell, start) > params.safe_radius
+                && stones
+                    .iter()
+                    .all(|&stone| chebyshev_distance(cell, stone) > params.corridor_width)
+        });
+        if !fits {
+            continue;
+        }
+
+        let mut candidate = stones.clone();
+        candidate.extend(wall);
+        if all_reachable(grid, start, &candidate) {
+            stones = candidate;
+        }
+    }
+
+    Level {
+        name: format!("Random #{}", seed),
+        stones: stones.iter().map(|pos| Stone::new(pos.x, pos.y)).collect(),
+        portals: vec![],
+        spawn_points: vec![],
+        start: Some(start),
+    }
+}
+
+// The level of a Random round, the seed also picks the symmetry
+pub fn generate_for_seed(seed: u64, grid: GridVector) -> Level {
+    let symmetries = Symmetry::all();
+    let params = GeneratorParams {
+        symmetry: symmetries[(seed % symmetries.len() as u64) as usize],
+        ..GeneratorParams::default()
+    };
+    generate(seed, grid, &params)
+}
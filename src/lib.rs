@@ -0,0 +1,53 @@
+extern crate clap;
+extern crate crossterm;
+extern crate ggez;
+extern crate gif;
+extern crate png;
+extern crate rand;
+extern crate rusttype;
+
+pub mod achievements;
+pub mod apple;
+pub mod arena;
+pub mod assets;
+pub mod base_types;
+pub mod cli;
+pub mod constants;
+pub mod editor;
+pub mod effects;
+pub mod export;
+pub mod frame;
+pub mod game;
+pub mod game_event;
+pub mod game_mode;
+pub mod grid;
+pub mod high_scores;
+pub mod http;
+pub mod info_screen;
+pub mod leaderboard;
+pub mod level;
+pub mod level_gen;
+pub mod lobby_screen;
+pub mod multiplayer;
+pub mod net;
+pub mod pathfinding;
+pub mod portal;
+pub mod power_up;
+pub mod profile;
+pub mod profile_screen;
+pub mod render;
+pub mod replay;
+pub mod settings;
+pub mod settings_screen;
+pub mod snake;
+pub mod sound;
+pub mod spectator_screen;
+pub mod stats;
+pub mod stone;
+pub mod stream;
+pub mod theme;
+pub mod tui;
+pub mod tween;
+pub mod ui;
+pub mod utils;
+pub mod viewport;
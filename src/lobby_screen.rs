@@ -0,0 +1,210 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{Font, Rect};
+use ggez::{Context, GameResult};
+use rand::random;
+
+use base_types::*;
+use constants::*;
+use net::{NetEvent, Session};
+use theme::Palette;
+use ui::{Button, Center, Label, List, Message, Stack, TextInput, Ui, UiEvent};
+
+const COLUMN_WIDTH: f32 = 260.0;
+
+fn button(text: &str, id: &'static str, font: Font) -> Button {
+    Button::new(text, 36.0, font, Rect::new(0.0, 0.0, COLUMN_WIDTH, 36.0)).with_id(id)
+}
+
+fn build_ui(names: &[String], address: &str, status: &str, area: Rect, font: Font) -> Ui {
+    let players = List::new("players", names.to_vec(), font, COLUMN_WIDTH, MAX_PLAYERS);
+
+    let actions = Stack::vertical(6.0)
+        .with(Label::new("Address", font, COLUMN_WIDTH))
+        .with(TextInput::new("address", address, 64, font, COLUMN_WIDTH))
+        .with(button("Host", "host", font))
+        .with(button("Join", "join", font))
+        .with(button("Start", "start", font))
+        .with(button("Leave", "leave", font))
+        .with(button("Back", "back", font));
+
+    let root = Stack::vertical(16.0)
+        .with(Label::new("Multiplayer", font, 2.0 * COLUMN_WIDTH))
+        .with(Stack::horizontal(30.0).with(players).with(actions))
+        .with(Label::new(status, font, 2.0 * COLUMN_WIDTH + 30.0));
+
+    Ui::new(Center::new(root), area)
+}
+
+pub struct LobbyScreen {
+    pub closed: bool,
+    pub session: Option<Session>,
+    // Seed and grid of a match that just started, with the events that followed it
+    pub started: Option<(u64, GridVector, Vec<NetEvent>)>,
+    name: String,
+    // Matches started from here are played on this grid
+    grid: GridVector,
+    address: String,
+    names: Vec<String>,
+    status: String,
+    ui: Ui,
+    area: Rect,
+    font: Font,
+}
+
+impl LobbyScreen {
+    pub fn new(name: &str, grid: GridVector, area: Rect, font: Font) -> Self {
+        let mut screen = LobbyScreen {
+            closed: false,
+            session: None,
+            started: None,
+            name: name.to_string(),
+            grid,
+            address: MULTIPLAYER_ADDRESS.to_string(),
+            names: vec![],
+            status: "Host a match or join one by address".to_string(),
+            ui: Ui::new(Stack::vertical(0.0), area),
+            area,
+            font,
+        };
+        screen.rebuild();
+        screen.ui.set_focus(Some(0));
+        screen
+    }
+
+    pub fn set_status(&mut self, status: &str) {
+        self.status = status.to_string();
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        let focus = self.ui.focus();
+        self.ui = build_ui(
+            &self.names,
+            &self.address,
+            &self.status,
+            self.area,
+            self.font,
+        );
+        self.ui.set_focus(focus);
+    }
+
+    pub fn connect(&mut self, address: &str, host: bool) {
+        self.address = address.to_string();
+        let session = if host {
+            Session::host(address, &self.name)
+        } else {
+            Session::join(address, &self.name)
+        };
+
+        match session {
+            Ok(session) => {
+                self.status = if host {
+                    format!("Hosting on {}, waiting for players", address)
+                } else {
+                    format!("Joined {}, waiting for the host", address)
+                };
+                self.names = session.names.clone();
+                self.session = Some(session);
+            }
+            Err(err) => {
+                eprintln!("Failed to connect: {}", err);
+                self.status = format!("Failed to connect to {}: {}", address, err);
+            }
+        }
+        self.rebuild();
+    }
+
+    fn leave(&mut self) {
+        if self.session.take().is_some() {
+            self.names.clear();
+            self.status = "Left the lobby".to_string();
+            self.rebuild();
+        }
+    }
+
+    pub fn update(&mut self, area: Rect) {
+        if self.area != area {
+            self.area = area;
+            self.rebuild();
+        }
+
+        for message in self.ui.poll() {
+            match message {
+                Message::TextChanged("address", text) => self.address = text,
+                Message::Clicked("host") | Message::Clicked("join") if self.session.is_some() => {
+                    self.set_status("Leave the current lobby first");
+                }
+                Message::Clicked("host") => {
+                    let address = self.address.clone();
+                    self.connect(&address, true);
+                }
+                Message::Submitted("address", _) | Message::Clicked("join") => {
+                    let address = self.address.clone();
+                    self.connect(&address, false);
+                }
+                Message::Clicked("start") => match self.session {
+                    Some(ref mut session) if session.is_host() => {
+                        session.start(random(), self.grid);
+                    }
+                    _ => self.set_status("Only the host can start the match"),
+                },
+                Message::Clicked("leave") => self.leave(),
+                Message::Clicked("back") => self.closed = true,
+                _ => {}
+            }
+        }
+
+        let events = match self.session {
+            Some(ref mut session) => session.poll(),
+            None => return,
+        };
+        let mut events = events.into_iter();
+        while let Some(event) = events.next() {
+            match event {
+                NetEvent::Lobby(names) => {
+                    self.names = names;
+                    self.rebuild();
+                }
+                NetEvent::Started { seed, grid } => {
+                    self.started = Some((seed, grid, events.collect()));
+                    return;
+                }
+                NetEvent::Closed(reason) => {
+                    self.session = None;
+                    self.names.clear();
+                    self.set_status(&format!("Disconnected: {}", reason));
+                    return;
+                }
+                NetEvent::Tick(..) | NetEvent::Left(_) => {}
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.ui.draw(ctx, palette)
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        if keycode == KeyCode::Escape {
+            self.closed = true;
+        } else {
+            self.ui.handle(UiEvent::Key(keycode, keymods));
+        }
+    }
+
+    pub fn notifiy_text(&mut self, character: char) {
+        self.ui.handle(UiEvent::Text(character));
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseDown(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseUp(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseMotion(x, y));
+    }
+}
@@ -1,228 +1,995 @@
 extern crate ggez;
 extern crate rand;
 
-mod apple;
-mod assets;
-mod base_types;
-mod button;
-mod constants;
-mod snake;
-mod stone;
-mod utils;
+extern crate ggez_snake;
 
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
 use std::time::Instant;
 
-use ggez::event::{quit, run, EventHandler, KeyCode, KeyMods, MouseButton};
+use ggez::conf::FullscreenType;
+use ggez::event::{
+    quit, run, Axis, Button as GamepadButton, EventHandler, GamepadId, KeyCode, KeyMods,
+    MouseButton,
+};
 use ggez::graphics::{
     clear, draw, present, Align, DrawMode, Font, Mesh, Rect, Scale, StrokeOptions, Text,
+    TextFragment,
 };
 use ggez::nalgebra as na;
-use ggez::{conf, ContextBuilder};
-use ggez::{Context, GameResult};
-
-use apple::Apple;
-use assets::Assets;
-use base_types::*;
-use button::Button;
-use constants::*;
-use snake::Snake;
-use stone::{level_corners, Stone};
-use utils::*;
-
-const WINDOW_W: f32 = GRID_SIZE.x as f32 * GRID_TILE_SIZE;
-const WINDOW_H: f32 = GRID_SIZE.y as f32 * GRID_TILE_SIZE + INFO_BAR_HIGHT;
-
-fn create_restart_button(text: &str, font: Font) -> GameResult<Button> {
-    Ok(Button::new(
-        text,
-        8.0 + FONT_DEFAULT_SIZE as f32,
+use ggez::{conf, filesystem, graphics, ContextBuilder};
+use ggez::{Context, GameError, GameResult};
+use rand::random;
+
+use ggez_snake::arena::Arena;
+use ggez_snake::assets::Assets;
+use ggez_snake::base_types::*;
+use ggez_snake::cli::{self, Options};
+use ggez_snake::constants::*;
+use ggez_snake::editor::Editor;
+use ggez_snake::effects::Effects;
+use ggez_snake::export::{self, ExportOptions};
+use ggez_snake::frame::Frame;
+use ggez_snake::game::Game;
+use ggez_snake::game_event::GameEvent;
+use ggez_snake::game_mode::{GameConfig, GameMode};
+use ggez_snake::grid::*;
+use ggez_snake::info_screen::InfoScreen;
+use ggez_snake::leaderboard::{self, Client, Reply};
+use ggez_snake::level::Level;
+use ggez_snake::level_gen;
+use ggez_snake::lobby_screen::LobbyScreen;
+use ggez_snake::multiplayer::Match;
+use ggez_snake::net::{NetEvent, Session};
+use ggez_snake::pathfinding::direction_between;
+use ggez_snake::portal::Portal;
+use ggez_snake::power_up::PowerUpKind;
+use ggez_snake::profile::Profile;
+use ggez_snake::profile_screen::ProfileScreen;
+use ggez_snake::render::Renderer;
+use ggez_snake::replay::Replay;
+use ggez_snake::settings::Settings;
+use ggez_snake::settings_screen::SettingsScreen;
+use ggez_snake::snake::Snake;
+use ggez_snake::sound::Sounds;
+use ggez_snake::spectator_screen::SpectatorScreen;
+use ggez_snake::stream::Broadcaster;
+use ggez_snake::theme::{load_custom_theme, Palette, Theme};
+use ggez_snake::tui;
+use ggez_snake::tween::{Easing, Tween};
+use ggez_snake::ui::{Button, Message, Stack, Ui, UiEvent};
+use ggez_snake::utils::*;
+use ggez_snake::viewport::Viewport;
+
+fn create_game_over_menu(selected: GameMode, online: bool, area: Rect, font: Font) -> Ui {
+    let restart = Button::new(
+        "Restart",
+        8.0 + FONT_DEFAULT_SIZE,
+        font,
+        Rect::new(0.0, 0.0, 110.0, 50.0),
+    );
+
+    let mut modes = Stack::horizontal(10.0);
+    for &mode in &GameMode::all() {
+        let mut button = Button::new(
+            mode.name(),
+            8.0 + FONT_DEFAULT_SIZE,
+            font,
+            Rect::new(0.0, 0.0, 150.0, 50.0),
+        );
+        button.set_highlighted(mode == selected);
+        modes.push(button.with_id(mode.id()));
+    }
+    let multiplayer = Button::new(
+        "Multiplayer",
+        8.0 + FONT_DEFAULT_SIZE,
+        font,
+        Rect::new(0.0, 0.0, 150.0, 50.0),
+    );
+    modes.push(multiplayer.with_id("multiplayer"));
+
+    let settings = Button::new(
+        "Settings",
+        8.0 + FONT_DEFAULT_SIZE,
         font,
-        Rect::new(
-            (WINDOW_W / 2.0) - (110.0 / 2.0),
-            (WINDOW_H / 2.0) - (50.0 / 2.0) + 120.0,
-            110.0,
-            50.0,
-        ),
-    ))
+        Rect::new(0.0, 0.0, 110.0, 50.0),
+    );
+
+    let stats = Button::new(
+        "Stats",
+        8.0 + FONT_DEFAULT_SIZE,
+        font,
+        Rect::new(0.0, 0.0, 110.0, 50.0),
+    );
+
+    let achievements = Button::new(
+        "Achievements",
+        8.0 + FONT_DEFAULT_SIZE,
+        font,
+        Rect::new(0.0, 0.0, 150.0, 50.0),
+    );
+
+    let profiles = Button::new(
+        "Profiles",
+        8.0 + FONT_DEFAULT_SIZE,
+        font,
+        Rect::new(0.0, 0.0, 110.0, 50.0),
+    );
+
+    let mut actions = Stack::horizontal(10.0)
+        .with(restart.with_id("restart"))
+        .with(settings.with_id("settings"))
+        .with(stats.with_id("stats"))
+        .with(achievements.with_id("achievements"))
+        .with(profiles.with_id("profiles"));
+    if online {
+        let leaderboard = Button::new(
+            "Leaderboard",
+            8.0 + FONT_DEFAULT_SIZE,
+            font,
+            Rect::new(0.0, 0.0, 150.0, 50.0),
+        );
+        actions.push(leaderboard.with_id("leaderboard"));
+    }
+    let root = Stack::vertical(85.0).with(actions).with(modes);
+    let top = area.h / 2.0 + 95.0;
+    let mut menu = Ui::new(root, Rect::new(area.x, top, area.w, area.h - top));
+    menu.set_focus(Some(0));
+    menu
+}
+
+fn gamepad_key(button: GamepadButton) -> Option<KeyCode> {
+    match button {
+        GamepadButton::DPadUp => Some(KeyCode::Up),
+        GamepadButton::DPadDown => Some(KeyCode::Down),
+        GamepadButton::DPadLeft => Some(KeyCode::Left),
+        GamepadButton::DPadRight => Some(KeyCode::Right),
+        GamepadButton::South | GamepadButton::Start => Some(KeyCode::Return),
+        _ => None,
+    }
 }
 
 struct MainState {
-    snake: Snake,
+    game: Game,
     inputs: Vec<KeyCode>,
-    apple: Apple,
-    stones: Vec<Stone>,
+    last_tick: Instant,
+    lost_at: Option<Instant>,
     assets: Assets,
     ui_update_needed: bool,
     score_text: Text,
+    profile_text: Text,
     game_over_text: Text,
+    high_scores_text: Text,
+    level_text: Text,
     play_again: bool,
-    restart_button: Option<Button>,
+    game_over_menu: Option<Ui>,
+    game_over_tweens: Vec<Tween>,
     game_over: bool,
+    mode: GameMode,
+    config: GameConfig,
+    levels: Vec<Level>,
+    level_index: usize,
+    level_arg: Option<Level>,
+    test_level: Option<Level>,
+    editor: Option<Editor>,
+    editing: bool,
+    viewport: Viewport,
+    profile: Profile,
+    settings_screen: Option<SettingsScreen>,
+    info_screen: Option<InfoScreen>,
+    profile_screen: Option<ProfileScreen>,
+    lobby_screen: Option<LobbyScreen>,
+    multiplayer: Option<Match>,
+    spectator_screen: Option<SpectatorScreen>,
+    // The grid of single player rounds, matches bring their own
+    grid: GridVector,
+    // The colors of the profile's theme
+    palette: Palette,
+    // Only set when the skin defines a custom theme
+    custom_palette: Option<Palette>,
+    broadcaster: Option<Broadcaster>,
+    sounds: Option<Sounds>,
+    stick_direction: Option<KeyCode>,
+    effects: Effects,
+    options: Options,
+    replay: Option<Replay>,
+    leaderboard: Option<Client>,
+    awaiting_leaderboard: bool,
+    online_rank: Option<usize>,
 }
 
-fn spawn_apple_in_area(area: &GridArea, conflicts: &[GridVector]) -> Apple {
-    let pos = loop {
-        let pos = random_pos(area);
+fn slide_rects(
+    grid: GridVector,
+    from: GridVector,
+    to: GridVector,
+    portals: &[Portal],
+    fraction: f32,
+) -> Vec<Rect> {
+    match direction_between(grid, from, to, portals) {
+        Some(direction) if from + direction == to => {
+            vec![slide_to_rect(&from, direction, fraction)]
+        }
+        Some(direction) => vec![
+            slide_to_rect(&from, direction, fraction),
+            slide_to_rect(&to, direction, fraction - 1.0),
+        ],
+        None => vec![gv_to_rect(&to)],
+    }
+}
 
-        if conflicts
-            .iter()
-            .all(|conflicting_pos| *conflicting_pos != pos)
-        {
-            break pos;
+fn snake_rects(
+    grid: GridVector,
+    snake: &Snake,
+    portals: &[Portal],
+    fraction: Option<f32>,
+) -> (Vec<Rect>, Vec<Rect>) {
+    let head = snake.head();
+    let fraction = match fraction {
+        Some(fraction) => fraction,
+        None => {
+            let body = snake.tail.iter().map(gv_to_rect).collect();
+            return (body, vec![gv_to_rect(&head)]);
         }
     };
 
-    Apple::new(pos)
+    let previous_head = snake.previous_tail[snake.previous_tail.len() - 1];
+    let head_rects = slide_rects(grid, previous_head, head, portals, fraction);
+    if snake.tail.len() == 1 {
+        return (head_rects.clone(), head_rects);
+    }
+
+    // The tail end stays drawn and the sliding piece behind it closes the gap as it leaves
+    let mut body: Vec<Rect> = slide_rects(
+        grid,
+        snake.previous_tail[0],
+        snake.tail[0],
+        portals,
+        fraction,
+    );
+    body.extend(snake.tail[..snake.tail.len() - 1].iter().map(gv_to_rect));
+    body.extend(head_rects.iter().cloned());
+
+    (body, head_rects)
 }
 
 impl MainState {
-    fn new(ctx: &mut Context) -> GameResult<MainState> {
-        let snake = Snake::new();
-        let apple = spawn_apple_in_area(&PLAY_AREA, &snake.tail);
-        let assets = Assets::load(ctx)?;
+    fn new(
+        ctx: &mut Context,
+        options: Options,
+        level_arg: Option<Level>,
+        replay: Option<Replay>,
+        broadcaster: Option<Broadcaster>,
+    ) -> GameResult<MainState> {
+        let custom_palette = match load_custom_theme(ctx) {
+            Ok(palette) => palette,
+            Err(err) => {
+                eprintln!("Failed to load custom theme: {}", err);
+                None
+            }
+        };
+        let profile = Profile::load_active(ctx);
+        let settings = &profile.settings;
+        let palette = settings.theme.palette(custom_palette);
+        let grid = match (&replay, options.grid) {
+            (Some(replay), _) => replay.grid,
+            (None, Some(grid)) => grid,
+            (None, None) => settings.grid_size.size(),
+        };
+
+        let assets = Assets::load(ctx);
         let mut score_text = Text::new("Score: 0");
         score_text.set_font(assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
         let mut game_over_text = Text::new("Game Over");
         game_over_text.set_font(assets.font, Scale::uniform(FONT_GAME_OVER_SIZE));
+        let high_scores_text = Text::new("");
+        let mode = match replay {
+            Some(ref replay) => replay.mode,
+            None => options.mode.unwrap_or(GameMode::Endless),
+        };
+        let config = mode.config();
+        if let Some(ref level) = level_arg {
+            if !level.fits(grid) {
+                return Err(GameError::ResourceLoadError(format!(
+                    "level {} doesn't fit a {}x{} grid",
+                    level.name, grid.x, grid.y
+                )));
+            }
+        }
+        let mut levels = Level::load_all(ctx, grid);
+        levels.extend(level_arg.iter().cloned());
+        let level_index = if level_arg.is_some() {
+            levels.len() - 1
+        } else {
+            0
+        };
+        let game = Game::new(
+            grid,
+            levels[level_index].clone(),
+            mode,
+            settings.speed.initial_speed(),
+            0,
+        );
+        let level_text = Text::new("");
+        let mut effects = Effects::new(assets.font);
+        effects.enabled = settings.effects;
+        let sounds = match Sounds::new(ctx, settings.volume) {
+            Ok(sounds) => Some(sounds),
+            Err(err) => {
+                eprintln!("Failed to create sounds: {}", err);
+                None
+            }
+        };
+        let (width, height) = graphics::size(ctx);
 
-        let state = MainState {
-            snake,
+        let mut state = MainState {
+            game,
             inputs: vec![],
-            apple,
-            stones: level_corners(),
+            last_tick: Instant::now(),
+            lost_at: None,
             assets,
             ui_update_needed: true,
             score_text,
+            profile_text: Text::new(""),
             game_over_text,
+            high_scores_text,
+            level_text,
             play_again: false,
-            restart_button: None,
+            game_over_menu: None,
+            game_over_tweens: vec![],
             game_over: false,
+            mode,
+            config,
+            levels,
+            level_index,
+            level_arg,
+            test_level: None,
+            editor: None,
+            editing: false,
+            viewport: Viewport::new(grid, width, height),
+            profile,
+            settings_screen: None,
+            info_screen: None,
+            profile_screen: None,
+            lobby_screen: None,
+            multiplayer: None,
+            spectator_screen: None,
+            grid,
+            palette,
+            custom_palette,
+            broadcaster,
+            sounds,
+            stick_direction: None,
+            effects,
+            leaderboard: options
+                .leaderboard
+                .as_ref()
+                .map(|server| Client::new(server)),
+            awaiting_leaderboard: false,
+            online_rank: None,
+            options,
+            replay: None,
+        };
+
+        state.reset(ctx);
+        if let Some(replay) = replay {
+            state.game = replay.start();
+            state.replay = Some(replay);
+        }
+
+        let session = match (&state.options.host, &state.options.join) {
+            (Some(address), _) => Some((address.clone(), true)),
+            (None, Some(address)) => Some((address.clone(), false)),
+            (None, None) => None,
         };
+        if let Some(ref address) = state.options.spectate {
+            state.spectator_screen = Some(SpectatorScreen::new(address, state.assets.font));
+        }
+
+        if let Some((address, host)) = session {
+            state.open_lobby();
+            if let Some(ref mut screen) = state.lobby_screen {
+                screen.connect(&address, host);
+            }
+        }
+
+        if state.profile.settings.fullscreen || state.options.fullscreen {
+            state.set_fullscreen(ctx, true)?;
+        } else {
+            state.viewport.apply(ctx)?;
+        }
 
         Ok(state)
     }
 
-    fn reset(&mut self) {
-        let snake = Snake::new();
-        let apple = spawn_apple_in_area(&PLAY_AREA, &snake.tail);
+    fn reset(&mut self, ctx: &mut Context) {
+        self.end_round(ctx);
+
+        let seed = self.options.seed.unwrap_or_else(random);
+        let level = match self.test_level {
+            Some(ref level) => level.clone(),
+            None if self.config.random_levels => level_gen::generate_for_seed(seed, self.grid),
+            None => self.levels[self.level_index].clone(),
+        };
+        let speed = self.options.speed.unwrap_or(self.profile.settings.speed);
 
-        self.snake = snake;
+        self.game = Game::new(self.grid, level, self.mode, speed.initial_speed(), seed);
         self.inputs = vec![];
-        self.apple = apple;
-        self.stones = level_corners();
+        self.last_tick = Instant::now();
+        self.lost_at = None;
         self.ui_update_needed = true;
         self.play_again = false;
-        self.restart_button = None;
+        self.game_over_menu = None;
+        self.game_over_tweens = vec![];
         self.game_over = false;
+        self.effects.clear();
+        self.profile.achievements.new_game();
+        self.online_rank = None;
+        self.replay = None;
+    }
+
+    fn load_levels(&mut self, ctx: &mut Context) {
+        self.levels = Level::load_all(ctx, self.grid);
+        if let Some(ref level) = self.level_arg {
+            if level.fits(self.grid) {
+                self.levels.push(level.clone());
+            } else {
+                eprintln!(
+                    "Failed to load level {}: it doesn't fit the grid",
+                    level.name
+                );
+            }
+        }
+    }
+
+    // Counts a round that is left before it's over in the stats
+    fn end_round(&mut self, ctx: &mut Context) {
+        if self.replay.is_some() || !self.game.is_started() || self.game.is_over() {
+            return;
+        }
+        self.game.end_round();
+        for event in self.game.events.drain(..) {
+            self.profile.stats.handle(&event, self.mode);
+        }
+        self.save_profile(ctx);
+    }
+
+    fn record_replay(&self) {
+        if let Some(ref path) = self.options.record {
+            if self.game.is_started() {
+                if let Err(err) = Replay::record(&self.game).save(path) {
+                    eprintln!("Failed to record replay: {}", err);
+                }
+            }
+        }
+    }
+
+    fn key_direction(&self, input: KeyCode) -> Option<GridVector> {
+        let keys = &self.profile.settings.key_bindings;
+        if input == keys.up || input == KeyCode::Up {
+            Some(GridVector { x: 0, y: -1 })
+        } else if input == keys.down || input == KeyCode::Down {
+            Some(GridVector { x: 0, y: 1 })
+        } else if input == keys.left || input == KeyCode::Left {
+            Some(GridVector { x: -1, y: 0 })
+        } else if input == keys.right || input == KeyCode::Right {
+            Some(GridVector { x: 1, y: 0 })
+        } else {
+            None
+        }
+    }
+
+    fn input_directions(&self) -> Vec<GridVector> {
+        self.inputs
+            .iter()
+            .filter_map(|&input| self.key_direction(input))
+            .collect()
+    }
+
+    fn tick_fraction(&self) -> f64 {
+        if self.game.is_started() && !self.game.is_over() {
+            (since(self.last_tick) / self.game.snake.speed).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn open_editor(&mut self) {
+        let level = self.game.level.clone();
+        self.editor = Some(Editor::new(self.game.grid, level, self.assets.font));
+        self.editing = true;
+    }
+
+    fn close_editor(&mut self, ctx: &mut Context) {
+        self.editor = None;
+        self.editing = false;
+        self.test_level = None;
+
+        let selected = self.levels[self.level_index].name.clone();
+        self.load_levels(ctx);
+        self.level_index = self
+            .levels
+            .iter()
+            .position(|level| level.name == selected)
+            .unwrap_or(0);
+        self.reset(ctx);
+    }
+
+    fn set_fullscreen(&mut self, ctx: &mut Context, fullscreen: bool) -> GameResult<()> {
+        let fullscreen_type = if fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Windowed
+        };
+        graphics::set_fullscreen(ctx, fullscreen_type)?;
+
+        let (width, height) = graphics::size(ctx);
+        self.resize(ctx, width, height)
+    }
+
+    fn resize(&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult<()> {
+        // Minimized windows report a zero size on some platforms, keep the old view
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(());
+        }
+
+        self.viewport = Viewport::new(self.viewport.grid, width, height);
+        self.viewport.apply(ctx)
+    }
+
+    fn apply_settings(&mut self, ctx: &mut Context, settings: Settings) {
+        let old = self.profile.settings.clone();
+        self.profile.settings = settings;
+
+        if self.profile.settings.theme != old.theme {
+            self.palette = self.profile.settings.theme.palette(self.custom_palette);
+        }
+
+        if self.profile.settings.grid_size != old.grid_size {
+            self.grid = self.profile.settings.grid_size.size();
+            self.load_levels(ctx);
+            self.level_index = 0;
+            self.test_level = None;
+            self.reset(ctx);
+
+            let grid = self.grid;
+            self.set_view_grid(ctx, grid);
+        }
+
+        if self.profile.settings.fullscreen != old.fullscreen {
+            if let Err(err) = self.set_fullscreen(ctx, self.profile.settings.fullscreen) {
+                eprintln!("Failed to toggle fullscreen: {}", err);
+            }
+        }
+
+        if self.profile.settings.speed != old.speed && !self.game.is_started() {
+            self.reset(ctx);
+        }
+
+        if let Some(ref mut sounds) = self.sounds {
+            sounds.set_volume(self.profile.settings.volume);
+        }
+        self.effects.enabled = self.profile.settings.effects;
+
+        self.save_profile(ctx);
+    }
+
+    fn open_lobby(&mut self) {
+        let name = match self.options.name {
+            Some(ref name) => name.clone(),
+            None => self.profile.name.clone(),
+        };
+        let area = self.viewport.area();
+        self.lobby_screen = Some(LobbyScreen::new(&name, self.grid, area, self.assets.font));
+    }
+
+    // The window follows the grid being played, matches use the host's
+    fn set_view_grid(&mut self, ctx: &mut Context, grid: GridVector) {
+        let (width, height) = graphics::size(ctx);
+        self.viewport = Viewport::new(grid, width, height);
+        if let Err(err) = self.viewport.apply(ctx) {
+            eprintln!("Failed to resize: {}", err);
+        }
+    }
+
+    fn publish(&self, frame: Frame) {
+        if let Some(ref broadcaster) = self.broadcaster {
+            broadcaster.publish(&frame);
+        }
+    }
+
+    fn save_profile(&mut self, ctx: &mut Context) {
+        if let Err(err) = self.profile.save(ctx) {
+            eprintln!("Failed to save profile: {}", err);
+        }
+    }
+
+    fn switch_profile(&mut self, ctx: &mut Context, name: &str) {
+        self.save_profile(ctx);
+        let profile = match Profile::load(ctx, name) {
+            Ok(profile) => profile,
+            Err(err) => {
+                eprintln!("Failed to load profile: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = Profile::set_active(ctx, name) {
+            eprintln!("Failed to save active profile: {}", err);
+        }
+
+        let settings = profile.settings.clone();
+        self.profile.name = profile.name;
+        self.profile.stats = profile.stats;
+        self.profile.achievements = profile.achievements;
+        self.profile.high_scores = profile.high_scores;
+        self.apply_settings(ctx, settings);
+        self.ui_update_needed = true;
+    }
+
+    fn select_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+        self.config = mode.config();
+    }
+
+    fn record_high_score(&mut self, ctx: &mut Context) {
+        let score = self.game.snake.score;
+        if self.profile.high_scores_mut(self.mode).insert(score) {
+            self.save_profile(ctx);
+        }
+    }
+
+    fn submit_score(&mut self) {
+        if self.replay.is_some() || self.game.snake.score == 0 {
+            return;
+        }
+        if let Some(ref mut client) = self.leaderboard {
+            client.submit(&self.profile.name, Replay::record(&self.game));
+        }
     }
 
     fn update_ui(&mut self) {
         let score_text = if self.game_over {
-            format!("Final Score: {}", self.snake.score)
+            format!("Final Score: {}", self.game.snake.score)
         } else {
-            format!("Score: {}", self.snake.score)
+            format!("Score: {}", self.game.snake.score)
         };
         self.score_text = Text::new(score_text);
         self.score_text
             .set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
+
+        self.profile_text = Text::new(self.profile.name.as_str());
+        self.profile_text
+            .set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
+
+        let high_scores = self.profile.high_scores(self.mode);
+        let mut high_scores_text = if high_scores.scores.is_empty() {
+            format!("No {} high scores yet", self.mode.name())
+        } else {
+            let scores: Vec<String> = high_scores
+                .scores
+                .iter()
+                .map(|score| score.to_string())
+                .collect();
+            format!("{} High Scores: {}", self.mode.name(), scores.join(", "))
+        };
+        if let Some(rank) = self.online_rank {
+            high_scores_text += &format!("  (online rank {})", rank);
+        }
+        self.high_scores_text = Text::new(high_scores_text);
+        self.high_scores_text
+            .set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
+
+        let level_text = match self.test_level {
+            Some(_) => "Testing level  (E to edit, Esc to return)".to_string(),
+            None if self.config.random_levels => {
+                format!("Level: {}  (E to edit)", self.game.level.name)
+            }
+            None => format!(
+                "Level: {}  (L to change, E to edit)",
+                self.levels[self.level_index].name
+            ),
+        };
+        self.level_text = Text::new(level_text);
+        self.level_text
+            .set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
     }
 }
 
 impl EventHandler for MainState {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
-        // Update snake
-        if !self.game_over {
-            let snake = &mut self.snake;
-
-            if let Some(lost_at) = snake.lost_at {
-                self.game_over = since(lost_at) > GAME_OVER_TIMEOUT;
-                self.ui_update_needed = true;
-            } else if since(snake.last_round) > snake.speed {
-                let mut new_velocity = None;
-                for input in &self.inputs {
-                    match *input {
-                        KeyCode::W if snake.velocity.y != 1 => {
-                            new_velocity = Some(GridVector { x: 0, y: -1 });
-                        }
-                        KeyCode::S if snake.velocity.y != -1 => {
-                            new_velocity = Some(GridVector { x: 0, y: 1 });
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // Update leaderboard
+        let replies = match self.leaderboard {
+            Some(ref mut client) => client.poll(),
+            None => vec![],
+        };
+        for reply in replies {
+            match reply {
+                Reply::Submitted(Ok(rank)) => {
+                    self.online_rank = Some(rank);
+                    self.ui_update_needed = true;
+                }
+                Reply::Submitted(Err(err)) => eprintln!("Failed to submit score: {}", err),
+                Reply::Fetched(mode, result) => {
+                    let rows = match result {
+                        Ok(ref entries) if entries.is_empty() => {
+                            vec![(format!("No {} scores yet", mode.name()), String::new())]
                         }
-                        KeyCode::A if snake.velocity.x != 1 => {
-                            new_velocity = Some(GridVector { x: -1, y: 0 });
+                        Ok(entries) => leaderboard::rows(&entries),
+                        Err(err) => {
+                            eprintln!("Failed to load leaderboard: {}", err);
+                            vec![("Failed to load leaderboard".to_string(), String::new())]
                         }
-                        KeyCode::D if snake.velocity.x != -1 => {
-                            new_velocity = Some(GridVector { x: 1, y: 0 });
+                    };
+                    if self.awaiting_leaderboard {
+                        if let Some(ref mut screen) = self.info_screen {
+                            screen.set_rows(rows);
                         }
-                        _ => {}
+                        self.awaiting_leaderboard = false;
                     }
                 }
+            }
+        }
 
-                self.inputs = vec![];
+        // Update settings screen
+        if let Some(mut screen) = self.settings_screen.take() {
+            screen.update(self.viewport.area());
+            if screen.settings != self.profile.settings {
+                self.apply_settings(ctx, screen.settings.clone());
+            }
+            if !screen.closed {
+                self.settings_screen = Some(screen);
+            }
+            return Ok(());
+        }
 
-                if let Some(velocity) = new_velocity {
-                    snake.velocity = velocity;
-                }
+        // Update info screen
+        if let Some(mut screen) = self.info_screen.take() {
+            screen.update(self.viewport.area());
+            if screen.closed {
+                self.awaiting_leaderboard = false;
+            } else {
+                self.info_screen = Some(screen);
+            }
+            return Ok(());
+        }
 
-                if snake.velocity != (GridVector { x: 0, y: 0 }) {
-                    let new_head;
-                    {
-                        let len = snake.tail.len();
-                        let last_head = &snake.tail[len - 1];
-                        new_head = wrap_in_grid(GridVector {
-                            x: last_head.x + snake.velocity.x,
-                            y: last_head.y + snake.velocity.y,
-                        });
-                    }
+        // Update profile screen
+        if let Some(mut screen) = self.profile_screen.take() {
+            screen.update(ctx, self.viewport.area());
+            if let Some(name) = screen.switch_to.take() {
+                self.switch_profile(ctx, &name);
+                screen.set_active(&self.profile.name);
+            }
+            if !screen.closed {
+                self.profile_screen = Some(screen);
+            }
+            return Ok(());
+        }
 
-                    for segment in &snake.tail {
-                        if *segment == new_head {
-                            snake.lost_at = Some(Instant::now());
-                        }
+        // Update spectator screen
+        if let Some(mut screen) = self.spectator_screen.take() {
+            screen.update();
+            let grid = screen.frame().map(|frame| frame.grid);
+            if screen.closed {
+                let grid = self.grid;
+                self.set_view_grid(ctx, grid);
+            } else {
+                if let Some(grid) = grid {
+                    if grid != self.viewport.grid {
+                        self.set_view_grid(ctx, grid);
                     }
+                }
+                self.spectator_screen = Some(screen);
+            }
+            return Ok(());
+        }
 
-                    for stone in &self.stones {
-                        if stone.pos == new_head {
-                            snake.lost_at = Some(Instant::now());
-                        }
-                    }
+        // Update multiplayer match
+        if let Some(mut game) = self.multiplayer.take() {
+            let tick = game.arena().tick;
+            game.update();
+            if game.arena().tick != tick {
+                self.publish(Frame::from_arena(game.arena()));
+            }
+            if game.closed {
+                let grid = self.grid;
+                self.set_view_grid(ctx, grid);
+                if let Some(ref mut screen) = self.lobby_screen {
+                    screen.set_status("Left the match");
+                }
+            } else {
+                let grid = game.arena().grid;
+                if grid != self.viewport.grid {
+                    self.set_view_grid(ctx, grid);
+                }
+                self.multiplayer = Some(game);
+            }
+            return Ok(());
+        }
 
-                    if new_head == self.apple.position {
-                        self.apple = spawn_apple_in_area(&PLAY_AREA, &snake.tail);
-                        snake.speed -= snake.speed / SPEED_INCREASE_FRACTION;
-                        snake.score += 1;
-                        self.ui_update_needed = true;
-                        snake.grow += GROW_PER_APPLE;
-                    }
+        // Update lobby screen
+        if let Some(mut screen) = self.lobby_screen.take() {
+            screen.update(self.viewport.area());
+            if let Some((seed, grid, backlog)) = screen.started.take() {
+                if let Some(session) = screen.session.take() {
+                    self.set_view_grid(ctx, grid);
+                    let game = Match::new(session, seed, grid, backlog, self.assets.font);
+                    self.multiplayer = Some(game);
+                }
+            }
+            if !screen.closed {
+                self.lobby_screen = Some(screen);
+            }
+            return Ok(());
+        }
 
-                    if snake.lost_at.is_none() {
-                        if snake.grow > 0 {
-                            snake.grow -= 1;
-                        } else {
-                            snake.tail.remove(0);
-                        }
-                        snake.tail.push(new_head);
-                    }
+        // Update editor
+        if self.editing {
+            let play_requested = match self.editor {
+                Some(ref mut editor) => editor.update(ctx)?,
+                None => false,
+            };
+            if play_requested {
+                self.test_level = self.editor.as_ref().map(|editor| editor.level.clone());
+                self.editing = false;
+                self.reset(ctx);
+            }
+            return Ok(());
+        }
+
+        // Update game
+        if !self.game_over {
+            let replay_finished = match self.replay {
+                Some(ref replay) => self.game.tick >= replay.ticks,
+                None => false,
+            };
+
+            if let Some(lost_at) = self.lost_at {
+                self.game_over = since(lost_at) > GAME_OVER_TIMEOUT;
+                self.ui_update_needed = true;
+
+                if self.game_over {
+                    self.record_high_score(ctx);
+                    self.submit_score();
                 }
+            } else if since(self.last_tick) > self.game.snake.speed && !replay_finished {
+                let directions = match self.replay {
+                    Some(ref replay) => replay.directions(self.game.tick),
+                    None => self.input_directions(),
+                };
+                self.inputs = vec![];
+
+                self.game.step(&directions);
+                self.last_tick = Instant::now();
+                self.publish(Frame::from_game(&self.game, &self.profile.name));
 
-                snake.last_round = Instant::now();
+                if self.game.is_over() {
+                    self.lost_at = Some(Instant::now());
+                    self.record_replay();
+                }
             }
         }
 
-        // Add restart button
+        // Update effects
         {
-            if self.restart_button.is_none() {
-                if let Some(lost_at) = self.snake.lost_at {
+            let mut profile_changed = false;
+            for event in self.game.events.drain(..) {
+                if let GameEvent::AppleEaten { .. } = event {
+                    self.ui_update_needed = true;
+                }
+                if self.replay.is_none() {
+                    self.profile.stats.handle(&event, self.mode);
+                    if self.profile.achievements.handle(&event) {
+                        profile_changed = true;
+                    }
+                    if let GameEvent::RoundEnded { .. } = event {
+                        profile_changed = true;
+                    }
+                }
+                self.effects.handle(&event);
+                if let Some(ref mut sounds) = self.sounds {
+                    if let Err(err) = sounds.play(&event) {
+                        eprintln!("Failed to play sound: {}", err);
+                    }
+                }
+            }
+            if profile_changed {
+                self.save_profile(ctx);
+            }
+            self.effects.update();
+            self.profile.achievements.update();
+        }
+
+        // Add game over menu
+        {
+            if self.game_over_menu.is_none() {
+                if let Some(lost_at) = self.lost_at {
                     if since(lost_at) > GAME_OVER_TIMEOUT {
-                        self.restart_button =
-                            Some(create_restart_button("Restart", self.assets.font)?);
+                        let online = self.leaderboard.is_some();
+                        let area = self.viewport.area();
+                        let mut menu =
+                            create_game_over_menu(self.mode, online, area, self.assets.font);
+                        let mut widgets = 0;
+                        menu.visit_mut(&mut |_| widgets += 1);
+
+                        self.game_over_menu = Some(menu);
+                        self.game_over_tweens = (0..4 + widgets)
+                            .map(|i| {
+                                let easing = if i == 0 {
+                                    Easing::BackOut
+                                } else {
+                                    Easing::CubicOut
+                                };
+                                Tween::new(0.0, 1.0, GAME_OVER_FADE_TIME, easing)
+                                    .delayed(i as f64 * GAME_OVER_STAGGER)
+                            })
+                            .collect();
                     }
                 }
             }
         }
 
-        if let Some(ref mut button) = self.restart_button {
-            if button.unhandled_click() {
-                self.play_again = true;
+        // Update game over menu
+        let mut messages = vec![];
+        if let Some(ref mut menu) = self.game_over_menu {
+            let tweens = &self.game_over_tweens;
+            let mut index = 4;
+            menu.visit_mut(&mut |widget| {
+                widget.set_opacity(tweens.get(index).map_or(1.0, Tween::value));
+                index += 1;
+            });
+
+            messages = menu.poll();
+        }
+        let area = self.viewport.area();
+        for message in messages {
+            match message {
+                Message::Clicked("restart") => self.play_again = true,
+                Message::Clicked("settings") => {
+                    let settings = self.profile.settings.clone();
+                    let screen = SettingsScreen::new(
+                        settings,
+                        Theme::all(self.custom_palette.is_some()),
+                        area,
+                        self.assets.font,
+                    );
+                    self.settings_screen = Some(screen);
+                }
+                Message::Clicked("stats") => {
+                    let rows = self.profile.stats.rows();
+                    let screen = InfoScreen::new("Stats", rows, 220.0, area, self.assets.font);
+                    self.info_screen = Some(screen);
+                }
+                Message::Clicked("leaderboard") => {
+                    if let Some(ref mut client) = self.leaderboard {
+                        client.fetch(self.mode, LEADERBOARD_SIZE);
+                        let rows = vec![("Loading...".to_string(), String::new())];
+                        let title = format!("{} Leaderboard", self.mode.name());
+                        let screen = InfoScreen::new(&title, rows, 300.0, area, self.assets.font);
+                        self.info_screen = Some(screen);
+                        self.awaiting_leaderboard = true;
+                    }
+                }
+                Message::Clicked("profiles") => {
+                    let screen =
+                        ProfileScreen::new(ctx, &self.profile.name, area, self.assets.font);
+                    self.profile_screen = Some(screen);
+                }
+                Message::Clicked("multiplayer") => self.open_lobby(),
+                Message::Clicked("achievements") => {
+                    let rows = self.profile.achievements.rows();
+                    let screen =
+                        InfoScreen::new("Achievements", rows, 560.0, area, self.assets.font);
+                    self.info_screen = Some(screen);
+                }
+                Message::Clicked(id) => {
+                    let mode = GameMode::all().iter().cloned().find(|mode| mode.id() == id);
+                    if let Some(mode) = mode {
+                        self.select_mode(mode);
+                        self.play_again = true;
+                    }
+                }
+                _ => {}
             }
         }
 
@@ -230,56 +997,179 @@ impl EventHandler for MainState {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        clear(ctx, COLOR_BACKGROUND);
+        let palette = self.palette;
+        clear(ctx, palette.background);
+
+        // Draw settings screen
+        if let Some(ref screen) = self.settings_screen {
+            screen.draw(ctx, palette)?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw info screen
+        if let Some(ref screen) = self.info_screen {
+            screen.draw(ctx, palette)?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw profile screen
+        if let Some(ref screen) = self.profile_screen {
+            screen.draw(ctx, palette)?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw spectator screen
+        if let Some(ref screen) = self.spectator_screen {
+            screen.draw(ctx, palette, self.viewport.area())?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw multiplayer match
+        if let Some(ref game) = self.multiplayer {
+            game.draw(ctx, palette)?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw lobby screen
+        if let Some(ref screen) = self.lobby_screen {
+            screen.draw(ctx, palette)?;
+            present(ctx)?;
+            return Ok(());
+        }
+
+        // Draw editor
+        if self.editing {
+            if let Some(ref mut editor) = self.editor {
+                editor.draw(ctx, palette)?;
+            }
+            present(ctx)?;
+            return Ok(());
+        }
+
+        let (shake_x, shake_y) = self.effects.shake_offset();
+        self.viewport.apply_offset(ctx, shake_x, shake_y)?;
+        let visual_time = self.game.time + self.tick_fraction() * self.game.snake.speed;
+
+        // Draw grid lines
+        if self.profile.settings.grid_lines {
+            let area = play_area(self.game.grid);
+            let color = fade(palette.foreground, 0.1);
+            let left = f32::from(area.pos.x) * GRID_TILE_SIZE - 2.0 * GRID_TILE_PADDING;
+            let top =
+                f32::from(area.pos.y) * GRID_TILE_SIZE - 2.0 * GRID_TILE_PADDING + INFO_BAR_HIGHT;
+            let right = left + f32::from(area.size.x) * GRID_TILE_SIZE;
+            let bottom = top + f32::from(area.size.y) * GRID_TILE_SIZE;
+
+            for x in 0..=area.size.x {
+                let x = left + f32::from(x) * GRID_TILE_SIZE;
+                let points = [na::Point2::new(x, top), na::Point2::new(x, bottom)];
+                let line = Mesh::new_line(ctx, &points, 1.0, color)?;
+                draw(ctx, &line, (na::Point2::new(0.0, 0.0),))?;
+            }
+            for y in 0..=area.size.y {
+                let y = top + f32::from(y) * GRID_TILE_SIZE;
+                let points = [na::Point2::new(left, y), na::Point2::new(right, y)];
+                let line = Mesh::new_line(ctx, &points, 1.0, color)?;
+                draw(ctx, &line, (na::Point2::new(0.0, 0.0),))?;
+            }
+        }
 
         // Draw apple
         if !self.game_over {
-            let apple = &self.apple;
-            let since_spawn = since(apple.spawned_at);
-            if since_spawn > APPLE_BLINK_TIME || blinks(since_spawn) {
+            let apple = &self.game.apple;
+            let spawn = Tween::new(0.0, 1.0, APPLE_BLINK_TIME, Easing::Linear)
+                .starting_at(ago(visual_time - apple.spawned_at));
+            if spawn.is_finished() || spawn.blinks() {
                 let rectangle = Mesh::new_rectangle(
                     ctx,
                     DrawMode::fill(),
                     gv_to_rect(&apple.position),
-                    COLOR_FOREGROUND,
+                    palette.foreground,
                 )?;
                 draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
             }
         }
 
+        // Draw power-up
+        if !self.game_over {
+            if let Some(ref power_up) = self.game.power_up {
+                let warning = Tween::new(1.0, 0.0, POWER_UP_EXPIRE_WARNING, Easing::Linear)
+                    .starting_at(ago(visual_time - power_up.spawned_at))
+                    .delayed(POWER_UP_LIFETIME - POWER_UP_EXPIRE_WARNING);
+                if warning.blinks() {
+                    let rectangle = Mesh::new_rectangle(
+                        ctx,
+                        DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                        scale_rect(gv_to_rect(&power_up.position), -1.0),
+                        palette.foreground,
+                    )?;
+                    draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                }
+            }
+        }
+
         // Draw snake
         {
-            let snake = &self.snake;
+            let snake = &self.game.snake;
 
-            if snake.lost_at.is_none() {
-                for segement in &snake.tail {
+            if self.lost_at.is_none() {
+                let fraction = if self.profile.settings.smooth_movement {
+                    Some(self.tick_fraction() as f32)
+                } else {
+                    None
+                };
+                let (body, head) =
+                    snake_rects(self.game.grid, snake, &self.game.level.portals, fraction);
+
+                for rect in &body {
+                    let rectangle =
+                        Mesh::new_rectangle(ctx, DrawMode::fill(), *rect, palette.foreground)?;
+                    draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                }
+                if snake.has_power_up(PowerUpKind::Ghost) {
+                    for rect in &body {
+                        let rectangle = Mesh::new_rectangle(
+                            ctx,
+                            DrawMode::fill(),
+                            scale_rect(*rect, -3.0),
+                            palette.background,
+                        )?;
+                        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                    }
+                }
+                for rect in &head {
                     let rectangle = Mesh::new_rectangle(
                         ctx,
                         DrawMode::fill(),
-                        gv_to_rect(segement),
-                        COLOR_FOREGROUND,
+                        scale_rect(*rect, -2.0),
+                        palette.background,
                     )?;
                     draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                    if snake.has_power_up(PowerUpKind::Shield) {
+                        let rectangle = Mesh::new_rectangle(
+                            ctx,
+                            DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+                            scale_rect(*rect, 3.0),
+                            palette.foreground,
+                        )?;
+                        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                    }
                 }
-                let segement = &snake.tail[snake.tail.len() - 1];
-                let rectangle = Mesh::new_rectangle(
-                    ctx,
-                    DrawMode::fill(),
-                    scale_rect(gv_to_rect(segement), -2.0),
-                    COLOR_BACKGROUND,
-                )?;
-                draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
-            } else if let Some(lost_at) = snake.lost_at {
-                let since_lost = since(lost_at);
-                if since_lost < BLINK_LENGTH_AFTER_DEATH
-                    && since_lost % (BLINK_INTERVAL * 2.0) < BLINK_INTERVAL
-                {
+            } else if let Some(lost_at) = self.lost_at {
+                let death = Tween::new(1.0, 0.0, BLINK_LENGTH_AFTER_DEATH, Easing::QuadIn)
+                    .starting_at(lost_at);
+                if !death.is_finished() && death.blinks() {
                     for segment in &snake.tail {
                         let rectangle = Mesh::new_rectangle(
                             ctx,
                             DrawMode::fill(),
                             gv_to_rect(segment),
-                            COLOR_FOREGROUND,
+                            fade(palette.foreground, death.value()),
                         )?;
                         draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
                     }
@@ -287,19 +1177,55 @@ impl EventHandler for MainState {
             }
         }
 
+        // Draw portals
+        if !self.game_over {
+            for portal in &self.game.level.portals {
+                for pos in &[portal.a, portal.b] {
+                    let rect = gv_to_rect(pos);
+                    let circle = Mesh::new_circle(
+                        ctx,
+                        DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                        na::Point2::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0),
+                        rect.w / 2.0 - 1.0,
+                        0.1,
+                        palette.foreground,
+                    )?;
+                    draw(ctx, &circle, (na::Point2::new(0.0, 0.0),))?;
+                }
+            }
+        }
+
         // Draw stones
         if !self.game_over {
-            for stone in &self.stones {
-                let rectangle = Mesh::new_rectangle(
-                    ctx,
-                    DrawMode::fill(),
-                    gv_to_rect(&stone.pos),
-                    COLOR_FOREGROUND,
-                )?;
+            let time = self.game.time;
+            for stone in &self.game.stones {
+                if !stone.is_spawned(self.game.snake.score) {
+                    continue;
+                }
+
+                let rectangle = if stone.is_active(time, self.game.snake.score) {
+                    Mesh::new_rectangle(
+                        ctx,
+                        DrawMode::fill(),
+                        gv_to_rect(&stone.pos),
+                        palette.foreground,
+                    )?
+                } else {
+                    Mesh::new_rectangle(
+                        ctx,
+                        DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+                        scale_rect(gv_to_rect(&stone.pos), -3.0),
+                        palette.foreground,
+                    )?
+                };
                 draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
             }
         }
 
+        // Draw effects
+        self.effects.draw(ctx, palette)?;
+        self.viewport.apply(ctx)?;
+
         // Draw UI
         {
             if self.ui_update_needed {
@@ -310,17 +1236,17 @@ impl EventHandler for MainState {
             if !self.game_over {
                 // Draw background
                 {
-                    let width = WINDOW_W as f32;
+                    let visible = self.viewport.visible_rect();
 
-                    let rect = Rect::new(0.0, 0.0, width, INFO_BAR_HIGHT);
+                    let rect = Rect::new(visible.x, 0.0, visible.w, INFO_BAR_HIGHT);
                     let rectangle =
-                        Mesh::new_rectangle(ctx, DrawMode::fill(), rect, COLOR_FOREGROUND)?;
+                        Mesh::new_rectangle(ctx, DrawMode::fill(), rect, palette.foreground)?;
                     draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
                     let rectangle = Mesh::new_rectangle(
                         ctx,
                         DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
                         scale_rect(rect, -2.0),
-                        COLOR_BACKGROUND,
+                        palette.background,
                     )?;
                     draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
                 }
@@ -328,41 +1254,146 @@ impl EventHandler for MainState {
                 // Draw content
                 {
                     let dest = na::Point2::new(
-                        12.0,
+                        self.viewport.visible_rect().x + 12.0,
                         (INFO_BAR_HIGHT - FONT_DEFAULT_SIZE as f32) / 2.0 - 4.0,
                     );
-                    draw(ctx, &self.score_text, (dest,))?;
+                    draw(ctx, &self.score_text, (dest, palette.background))?;
+
+                    let dest =
+                        na::Point2::new(dest.x + self.score_text.width(ctx) as f32 + 30.0, dest.y);
+                    draw(ctx, &self.profile_text, (dest, palette.background))?;
+                }
+
+                // Draw active power-ups
+                {
+                    let mut x = self.viewport.visible_rect().x
+                        + 12.0
+                        + self.score_text.width(ctx) as f32
+                        + 30.0
+                        + self.profile_text.width(ctx) as f32
+                        + 30.0;
+                    for power_up in &self.game.snake.power_ups {
+                        let dest =
+                            na::Point2::new(x, (INFO_BAR_HIGHT - FONT_DEFAULT_SIZE) / 2.0 - 4.0);
+                        let frag = TextFragment::new(power_up.kind.icon());
+                        let mut icon = Text::new(frag.color(palette.background));
+                        icon.set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
+                        draw(ctx, &icon, (dest,))?;
+                        x += icon.width(ctx) as f32 + 6.0;
+
+                        let bar =
+                            Rect::new(x, INFO_BAR_HIGHT / 2.0 - 5.0, POWER_UP_BAR_WIDTH, 10.0);
+                        let rectangle = Mesh::new_rectangle(
+                            ctx,
+                            DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+                            bar,
+                            palette.background,
+                        )?;
+                        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+                        let remaining = power_up.remaining(self.game.time) as f32;
+                        if remaining > 0.0 {
+                            let fill = Rect::new(bar.x, bar.y, bar.w * remaining, bar.h);
+                            let rectangle = Mesh::new_rectangle(
+                                ctx,
+                                DrawMode::fill(),
+                                fill,
+                                palette.background,
+                            )?;
+                            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                        }
+                        x += POWER_UP_BAR_WIDTH + 24.0;
+                    }
+                }
+
+                // Draw mode and countdown
+                {
+                    let status = match self.config.time_limit {
+                        Some(limit) => {
+                            let remaining = (limit - self.game.time).max(0.0).ceil() as u32;
+                            format!(
+                                "{}  {}:{:02}",
+                                self.mode.name(),
+                                remaining / 60,
+                                remaining % 60
+                            )
+                        }
+                        None => self.mode.name().to_string(),
+                    };
+                    let frag = TextFragment::new(status);
+                    let mut text = Text::new(frag.color(palette.background));
+                    text.set_font(self.assets.font, Scale::uniform(FONT_DEFAULT_SIZE));
+                    let visible = self.viewport.visible_rect();
+                    text.set_bounds(
+                        na::Point2::new(visible.w - 24.0, INFO_BAR_HIGHT),
+                        Align::Right,
+                    );
+                    let dest = na::Point2::new(
+                        visible.x + 12.0,
+                        (INFO_BAR_HIGHT - FONT_DEFAULT_SIZE) / 2.0 - 4.0,
+                    );
+                    draw(ctx, &text, (dest,))?;
                 }
             }
 
             if self.game_over {
+                let reveal: Vec<f32> = self.game_over_tweens.iter().map(Tween::value).collect();
+                let reveal = |i: usize| reveal.get(i).cloned().unwrap_or(1.0);
+                let slide = |i: usize| (1.0 - reveal(i)) * GAME_OVER_SLIDE;
+                let color = |i: usize| fade(palette.foreground, reveal(i));
+                let area = self.viewport.area();
+
                 // Draw game over text
                 let dest = na::Point2::new(
                     0.0,
-                    (WINDOW_H / 2.0) - (FONT_GAME_OVER_SIZE as f32 * 1.4) / 2.0 - 60.0,
+                    (area.h / 2.0) - (FONT_GAME_OVER_SIZE as f32 * 1.4) / 2.0 - 60.0 + slide(0),
                 );
                 self.game_over_text
-                    .set_bounds(na::Point2::new(WINDOW_W, WINDOW_H), Align::Center);
-                draw(ctx, &self.game_over_text, (dest,))?;
+                    .set_bounds(na::Point2::new(area.w, area.h), Align::Center);
+                draw(ctx, &self.game_over_text, (dest, color(0)))?;
 
                 // Draw final score
                 let dest = na::Point2::new(
                     0.0,
-                    (WINDOW_H / 2.0) - (FONT_DEFAULT_SIZE as f32 * 1.4) / 2.0 + 10.0,
+                    (area.h / 2.0) - (FONT_DEFAULT_SIZE as f32 * 1.4) / 2.0 + 10.0 + slide(1),
                 );
                 self.score_text
-                    .set_bounds(na::Point2::new(WINDOW_W, WINDOW_H), Align::Center);
-                draw(ctx, &self.score_text, (dest,))?;
+                    .set_bounds(na::Point2::new(area.w, area.h), Align::Center);
+                draw(ctx, &self.score_text, (dest, color(1)))?;
 
-                // Draw reset button
-                if let Some(ref mut button) = self.restart_button {
-                    button.draw(ctx)?;
+                // Draw high scores
+                let dest = na::Point2::new(
+                    0.0,
+                    (area.h / 2.0) - (FONT_DEFAULT_SIZE * 1.4) / 2.0 + 50.0 + slide(2),
+                );
+                self.high_scores_text
+                    .set_bounds(na::Point2::new(area.w, area.h), Align::Center);
+                draw(ctx, &self.high_scores_text, (dest, color(2)))?;
+
+                // Draw level
+                let dest = na::Point2::new(
+                    0.0,
+                    (area.h / 2.0) - (FONT_DEFAULT_SIZE * 1.4) / 2.0 + 78.0 + slide(3),
+                );
+                self.level_text
+                    .set_bounds(na::Point2::new(area.w, area.h), Align::Center);
+                draw(ctx, &self.level_text, (dest, color(3)))?;
+
+                // Draw menu
+                if let Some(ref menu) = self.game_over_menu {
+                    menu.draw(ctx, palette)?;
                 }
             }
         }
 
+        // Draw achievement toasts
+        let area = self.viewport.area();
+        self.profile
+            .achievements
+            .draw(ctx, palette, area, self.assets.font)?;
+
         if self.game_over && self.play_again {
-            self.reset();
+            self.reset(ctx);
         }
 
         present(ctx)?;
@@ -373,17 +1404,122 @@ impl EventHandler for MainState {
         &mut self,
         ctx: &mut Context,
         keycode: KeyCode,
-        _keymod: KeyMods,
+        keymod: KeyMods,
         _repeat: bool,
     ) {
+        if keycode == KeyCode::F11 {
+            let mut settings = self.profile.settings.clone();
+            settings.fullscreen = !settings.fullscreen;
+            if let Some(ref mut screen) = self.settings_screen {
+                screen.set_settings(settings.clone());
+            }
+            self.apply_settings(ctx, settings);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.settings_screen {
+            screen.notifiy_key_down(keycode, keymod);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.info_screen {
+            screen.notifiy_key_down(keycode, keymod);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.profile_screen {
+            screen.notifiy_key_down(keycode, keymod);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.spectator_screen {
+            screen.notifiy_key_down(keycode, keymod);
+            return;
+        }
+
+        if self.multiplayer.is_some() {
+            let direction = self.key_direction(keycode);
+            if let Some(ref mut game) = self.multiplayer {
+                match direction {
+                    Some(direction) => game.notifiy_direction(direction),
+                    None => game.notifiy_key_down(keycode, keymod),
+                }
+            }
+            return;
+        }
+
+        if let Some(ref mut screen) = self.lobby_screen {
+            screen.notifiy_key_down(keycode, keymod);
+            return;
+        }
+
+        if self.editing {
+            if keycode == KeyCode::Escape {
+                let close = match self.editor {
+                    Some(ref mut editor) => editor.request_close(),
+                    None => true,
+                };
+                if close {
+                    self.close_editor(ctx);
+                }
+            } else if let Some(ref mut editor) = self.editor {
+                editor.notifiy_key_down(keycode, keymod);
+            }
+            return;
+        }
+
         self.inputs.push(keycode);
 
-        if keycode == KeyCode::Space && self.restart_button.is_some() {
-            self.play_again = true;
+        if let Some(ref mut menu) = self.game_over_menu {
+            menu.handle(UiEvent::Key(keycode, keymod));
+        }
+
+        if self.game_over_menu.is_some() {
+            let mode = match keycode {
+                KeyCode::Key1 => Some(GameMode::Endless),
+                KeyCode::Key2 => Some(GameMode::TimeAttack),
+                KeyCode::Key3 => Some(GameMode::Survival),
+                KeyCode::Key4 => Some(GameMode::Zen),
+                KeyCode::Key5 => Some(GameMode::Random),
+                _ => None,
+            };
+            if let Some(mode) = mode {
+                self.select_mode(mode);
+                self.play_again = true;
+            }
+
+            if keycode == KeyCode::L && self.test_level.is_none() && !self.config.random_levels {
+                self.level_index = (self.level_index + 1) % self.levels.len();
+                self.ui_update_needed = true;
+            }
+
+            if keycode == KeyCode::E {
+                self.open_editor();
+            }
+
+            if keycode == KeyCode::M {
+                self.open_lobby();
+            }
         }
 
         if keycode == KeyCode::Escape {
-            quit(ctx);
+            if self.editor.is_some() {
+                self.editing = true;
+            } else {
+                self.record_replay();
+                self.end_round(ctx);
+                quit(ctx);
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        if let Some(ref mut screen) = self.profile_screen {
+            screen.notifiy_text(character);
+        } else if let Some(ref mut screen) = self.lobby_screen {
+            if self.multiplayer.is_none() {
+                screen.notifiy_text(character);
+            }
         }
     }
 
@@ -394,31 +1530,436 @@ impl EventHandler for MainState {
     }
 
     fn mouse_button_down_event(&mut self, _ctx: &mut Context, mb: MouseButton, x: f32, y: f32) {
-        if let Some(ref mut button) = self.restart_button {
-            button.notifiy_mouse_down(mb, x, y);
+        let (x, y) = self.viewport.to_logical(x, y);
+
+        if let Some(ref mut screen) = self.settings_screen {
+            screen.notifiy_mouse_down(mb, x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.info_screen {
+            screen.notifiy_mouse_down(mb, x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.profile_screen {
+            screen.notifiy_mouse_down(mb, x, y);
+            return;
+        }
+
+        if self.multiplayer.is_some() || self.spectator_screen.is_some() {
+            return;
+        }
+
+        if let Some(ref mut screen) = self.lobby_screen {
+            screen.notifiy_mouse_down(mb, x, y);
+            return;
+        }
+
+        if self.editing {
+            if let Some(ref mut editor) = self.editor {
+                editor.notifiy_mouse_down(mb, x, y);
+            }
+            return;
+        }
+
+        if let Some(ref mut menu) = self.game_over_menu {
+            menu.handle(UiEvent::MouseDown(mb, x, y));
         }
     }
 
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, mb: MouseButton, x: f32, y: f32) {
-        if let Some(ref mut button) = self.restart_button {
-            button.notifiy_mouse_up(mb, x, y);
+        let (x, y) = self.viewport.to_logical(x, y);
+
+        if let Some(ref mut screen) = self.settings_screen {
+            screen.notifiy_mouse_up(mb, x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.info_screen {
+            screen.notifiy_mouse_up(mb, x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.profile_screen {
+            screen.notifiy_mouse_up(mb, x, y);
+            return;
+        }
+
+        if self.multiplayer.is_some() || self.spectator_screen.is_some() {
+            return;
+        }
+
+        if let Some(ref mut screen) = self.lobby_screen {
+            screen.notifiy_mouse_up(mb, x, y);
+            return;
+        }
+
+        if self.editing {
+            if let Some(ref mut editor) = self.editor {
+                editor.notifiy_mouse_up(mb, x, y);
+            }
+            return;
+        }
+
+        if let Some(ref mut menu) = self.game_over_menu {
+            menu.handle(UiEvent::MouseUp(mb, x, y));
         }
     }
 
     fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _xrel: f32, _yrel: f32) {
-        if let Some(ref mut button) = self.restart_button {
-            button.notifiy_mouse_motion(x, y);
+        let (x, y) = self.viewport.to_logical(x, y);
+
+        if let Some(ref mut screen) = self.settings_screen {
+            screen.notifiy_mouse_motion(x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.info_screen {
+            screen.notifiy_mouse_motion(x, y);
+            return;
+        }
+
+        if let Some(ref mut screen) = self.profile_screen {
+            screen.notifiy_mouse_motion(x, y);
+            return;
+        }
+
+        if self.multiplayer.is_some() || self.spectator_screen.is_some() {
+            return;
+        }
+
+        if let Some(ref mut screen) = self.lobby_screen {
+            screen.notifiy_mouse_motion(x, y);
+            return;
+        }
+
+        if self.editing {
+            if let Some(ref mut editor) = self.editor {
+                editor.notifiy_mouse_motion(x, y);
+            }
+            return;
+        }
+
+        if let Some(ref mut menu) = self.game_over_menu {
+            menu.handle(UiEvent::MouseMotion(x, y));
+        }
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: GamepadButton,
+        _id: GamepadId,
+    ) {
+        if let Some(keycode) = gamepad_key(button) {
+            self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+        }
+    }
+
+    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        let direction = match axis {
+            Axis::LeftStickX if value > GAMEPAD_STICK_THRESHOLD => Some(KeyCode::Right),
+            Axis::LeftStickX if value < -GAMEPAD_STICK_THRESHOLD => Some(KeyCode::Left),
+            Axis::LeftStickY if value > GAMEPAD_STICK_THRESHOLD => Some(KeyCode::Up),
+            Axis::LeftStickY if value < -GAMEPAD_STICK_THRESHOLD => Some(KeyCode::Down),
+            Axis::LeftStickX | Axis::LeftStickY => None,
+            _ => return,
+        };
+
+        if direction != self.stick_direction {
+            self.stick_direction = direction;
+            if let Some(keycode) = direction {
+                self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+            }
+        }
+    }
+
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        self.record_replay();
+        self.end_round(ctx);
+        false
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        if let Err(err) = self.resize(ctx, width, height) {
+            eprintln!("Failed to resize: {}", err);
+        }
+    }
+}
+
+fn run_headless(replay: &Replay, record: Option<&PathBuf>) -> i32 {
+    let game = replay.simulate();
+    println!("mode: {}", game.mode.id());
+    println!("ticks: {}", game.tick);
+    println!("score: {}", game.snake.score);
+    println!("length: {}", game.snake.tail.len());
+
+    if let Some(path) = record {
+        if let Err(err) = Replay::record(&game).save(path) {
+            eprintln!("error: failed to record replay: {}", err);
+            return EXIT_FAILURE;
+        }
+    }
+
+    if !replay.matches(&game) {
+        eprintln!(
+            "error: replay does not match its recording (expected score {} after {} ticks)",
+            replay.score, replay.ticks
+        );
+        return EXIT_REPLAY_MISMATCH;
+    }
+
+    0
+}
+
+fn run_screenshot(replay: &Replay, options: &Options, path: &Path) -> i32 {
+    let game = replay.simulate_to(options.tick.unwrap_or(replay.ticks));
+    let pixmap = Renderer::new(options.theme.unwrap_or(Theme::Dark).palette(None))
+        .render(&game, "")
+        .scaled(options.scale);
+    if let Err(err) = pixmap.save_png(path) {
+        eprintln!("error: failed to save screenshot: {}", err);
+        return EXIT_FAILURE;
+    }
+
+    0
+}
+
+fn run_export(replay: &Replay, options: &Options, path: &Path) -> i32 {
+    let export_options = ExportOptions {
+        scale: options.scale,
+        fps: options.fps,
+        theme: options.theme.unwrap_or(Theme::Dark),
+    };
+    match export::export(replay, path, &export_options) {
+        Ok(frames) => {
+            println!("frames: {}", frames);
+            0
+        }
+        Err(err) => {
+            eprintln!("error: failed to export replay: {}", err);
+            EXIT_FAILURE
+        }
+    }
+}
+
+// Plays a match with a bot steering towards the apple, the printed checksum lets
+// several processes compare their simulations. A streaming host plays in real
+// time so spectators can follow.
+fn run_headless_match(options: &Options, broadcaster: Option<&Broadcaster>) -> i32 {
+    let name = options
+        .name
+        .as_ref()
+        .map_or(DEFAULT_PROFILE_NAME, |name| name.as_str());
+    let session = match (&options.host, &options.join) {
+        (Some(address), _) => Session::host(address, name),
+        (None, Some(address)) => Session::join(address, name),
+        (None, None) => return EXIT_FAILURE,
+    };
+    let mut session = match session {
+        Ok(session) => session,
+        Err(err) => {
+            eprintln!("error: failed to connect: {}", err);
+            return EXIT_FAILURE;
+        }
+    };
+
+    let mut arena: Option<Arena> = None;
+    let mut last_tick = Instant::now();
+    loop {
+        if session.is_host() && !session.started && session.names.len() >= options.players {
+            let seed = options.seed.unwrap_or_else(random);
+            session.start(seed, options.grid.unwrap_or(DEFAULT_GRID_SIZE));
         }
+
+        for event in session.poll() {
+            match event {
+                NetEvent::Lobby(names) => println!("lobby: {}", names.join(", ")),
+                NetEvent::Started { seed, grid } => {
+                    println!("seed: {}", seed);
+                    let new_arena = Arena::new(grid, &session.names, seed);
+                    session.send_input(0, new_arena.bot_direction(session.local_id));
+                    arena = Some(new_arena);
+                }
+                NetEvent::Tick(tick, directions) => {
+                    let arena = match arena {
+                        Some(ref mut arena) if arena.tick == tick => arena,
+                        _ => continue,
+                    };
+                    arena.step(&directions);
+                    arena.events.clear();
+                    if let Some(broadcaster) = broadcaster {
+                        broadcaster.publish(&Frame::from_arena(arena));
+                    }
+
+                    if arena.is_over() || arena.tick >= MULTIPLAYER_TICK_LIMIT {
+                        println!("ticks: {}", arena.tick);
+                        println!("checksum: {:016x}", arena.checksum());
+                        for player in &arena.players {
+                            println!("score: {} {}", player.name, player.snake.score);
+                        }
+                        match arena.winner() {
+                            Some(id) => println!("winner: {}", arena.players[id].name),
+                            None => println!("winner: none"),
+                        }
+                        return 0;
+                    }
+                    session.send_input(tick + 1, arena.bot_direction(session.local_id));
+                }
+                NetEvent::Left(id) => {
+                    if let Some(ref mut arena) = arena {
+                        arena.disconnect(id);
+                    }
+                }
+                NetEvent::Closed(reason) => {
+                    eprintln!("error: disconnected: {}", reason);
+                    return EXIT_FAILURE;
+                }
+            }
+        }
+
+        let due = match (broadcaster, &arena) {
+            (Some(_), Some(arena)) => since(last_tick) > arena.speed,
+            _ => true,
+        };
+        if due && session.advance() {
+            last_tick = Instant::now();
+        }
+        thread::sleep(NET_POLL_INTERVAL);
     }
 }
 
 pub fn main() {
+    let options = match cli::parse_args() {
+        Ok(options) => options,
+        Err(err) => {
+            if err.use_stderr() {
+                eprintln!("{}", err.message);
+                process::exit(EXIT_USAGE);
+            }
+            println!("{}", err.message);
+            return;
+        }
+    };
+
+    // Without --grid the window plays on the profile's grid, which is checked later
+    let level_grid = match options.grid {
+        Some(grid) => grid,
+        None if options.tui => DEFAULT_GRID_SIZE,
+        None => GridVector::new(MAX_GRID_SIZE, MAX_GRID_SIZE),
+    };
+    let level = match options.level {
+        Some(ref path) => match Level::load_file(path, level_grid) {
+            Ok(level) => Some(level),
+            Err(err) => {
+                eprintln!("error: failed to load level: {}", err);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        None => None,
+    };
+
+    let replay = match options.replay {
+        Some(ref path) => match Replay::load(path) {
+            Ok(replay) => Some(replay),
+            Err(err) => {
+                eprintln!("error: failed to load replay: {}", err);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(ref path) = options.resources {
+        if !path.is_dir() {
+            eprintln!("error: resources directory `{}` not found", path.display());
+            process::exit(EXIT_FAILURE);
+        }
+    }
+
+    if let (Some(ref path), Some(ref replay)) = (&options.screenshot, &replay) {
+        process::exit(run_screenshot(replay, &options, path));
+    }
+    if let (Some(ref path), Some(ref replay)) = (&options.export, &replay) {
+        process::exit(run_export(replay, &options, path));
+    }
+
+    let broadcaster = match options.stream {
+        Some(ref address) => match Broadcaster::bind(address) {
+            Ok(broadcaster) => Some(broadcaster),
+            Err(err) => {
+                eprintln!("error: failed to stream on {}: {}", address, err);
+                process::exit(EXIT_FAILURE);
+            }
+        },
+        None => None,
+    };
+
+    if options.tui {
+        match tui::run(&options, &level, &replay) {
+            Ok(game) => {
+                if let Some(ref path) = options.record {
+                    if game.is_started() && replay.is_none() {
+                        if let Err(err) = Replay::record(&game).save(path) {
+                            eprintln!("error: failed to record replay: {}", err);
+                            process::exit(EXIT_FAILURE);
+                        }
+                    }
+                }
+                process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("error: terminal failed: {}", err);
+                process::exit(EXIT_RUNTIME);
+            }
+        }
+    }
+
+    if options.headless {
+        if let Some(ref replay) = replay {
+            process::exit(run_headless(replay, options.record.as_ref()));
+        }
+        process::exit(run_headless_match(&options, broadcaster.as_ref()));
+    }
+
+    // The profile's grid is only known once the window is open, it resizes then
+    let window_grid = match (&replay, options.grid) {
+        (Some(replay), _) => replay.grid,
+        (None, Some(grid)) => grid,
+        (None, None) => DEFAULT_GRID_SIZE,
+    };
     let cb = ContextBuilder::new("snake", "moritzkn")
         .window_setup(conf::WindowSetup::default().title("Snake"))
-        .window_mode(conf::WindowMode::default().dimensions(WINDOW_W, WINDOW_H));
+        .window_mode(
+            conf::WindowMode::default()
+                .dimensions(window_w(window_grid), window_h(window_grid))
+                .resizable(true)
+                .min_dimensions(window_w(window_grid) / 2.0, window_h(window_grid) / 2.0),
+        );
 
-    let (ctx, event_loop) = &mut cb.build().unwrap();
+    let (ctx, event_loop) = &mut match cb.build() {
+        Ok(context) => context,
+        Err(err) => {
+            eprintln!("error: failed to open a window: {}", err);
+            process::exit(EXIT_NO_DISPLAY);
+        }
+    };
+
+    if let Some(ref path) = options.resources {
+        filesystem::mount(ctx, path, true);
+    }
 
-    let state = &mut MainState::new(ctx).unwrap();
-    run(ctx, event_loop, state).unwrap();
+    let state = &mut match MainState::new(ctx, options, level, replay, broadcaster) {
+        Ok(state) => state,
+        Err(err) => {
+            eprintln!("error: failed to start the game: {}", err);
+            process::exit(EXIT_STARTUP);
+        }
+    };
+
+    if let Err(err) = run(ctx, event_loop, state) {
+        eprintln!("error: {}", err);
+        process::exit(EXIT_RUNTIME);
+    }
 }
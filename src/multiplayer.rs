@@ -0,0 +1,167 @@
+use std::time::Instant;
+
+use ggez::event::{KeyCode, KeyMods};
+use ggez::graphics::{draw, Align, DrawMode, Font, Mesh, Rect, Scale, Text};
+use ggez::graphics::{Color, TextFragment};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+use rand::random;
+
+use arena::Arena;
+use base_types::*;
+use constants::*;
+use frame::Frame;
+use grid::*;
+use net::{NetEvent, Session};
+use theme::Palette;
+use utils::*;
+
+fn text(content: &str, color: Color, font: Font) -> Text {
+    let mut text = Text::new(TextFragment::new(content).color(color));
+    text.set_font(font, Scale::uniform(FONT_DEFAULT_SIZE));
+    text
+}
+
+// A running lockstep match, the arena only advances on ticks relayed by the host
+pub struct Match {
+    pub closed: bool,
+    session: Session,
+    arena: Arena,
+    last_tick: Instant,
+    direction: Option<GridVector>,
+    status: Option<String>,
+    font: Font,
+}
+
+impl Match {
+    pub fn new(
+        session: Session,
+        seed: u64,
+        grid: GridVector,
+        backlog: Vec<NetEvent>,
+        font: Font,
+    ) -> Self {
+        let arena = Arena::new(grid, &session.names, seed);
+
+        let mut game = Match {
+            closed: false,
+            session,
+            arena,
+            last_tick: Instant::now(),
+            direction: None,
+            status: None,
+            font,
+        };
+        game.session.send_input(0, GridVector::new(0, 0));
+        for event in backlog {
+            game.handle(event);
+        }
+        game
+    }
+
+    fn handle(&mut self, event: NetEvent) {
+        match event {
+            NetEvent::Started { seed, grid } => {
+                self.arena = Arena::new(grid, &self.session.names, seed);
+                self.direction = None;
+                self.session.send_input(0, GridVector::new(0, 0));
+            }
+            NetEvent::Tick(tick, directions) => {
+                if tick != self.arena.tick {
+                    return;
+                }
+                self.arena.step(&directions);
+                self.arena.events.clear();
+                if !self.arena.is_over() {
+                    let direction = self.direction.take().unwrap_or(GridVector::new(0, 0));
+                    self.session.send_input(tick + 1, direction);
+                }
+            }
+            NetEvent::Left(id) => {
+                self.arena.disconnect(id);
+                if let Some(player) = self.arena.players.get(id) {
+                    self.status = Some(format!("{} left the match", player.name));
+                }
+            }
+            NetEvent::Closed(reason) => {
+                self.status = Some(format!("Disconnected: {}", reason));
+            }
+            NetEvent::Lobby(_) => {}
+        }
+    }
+
+    pub fn update(&mut self) {
+        for event in self.session.poll() {
+            self.handle(event);
+        }
+
+        let due = since(self.last_tick) > self.arena.speed;
+        if self.session.is_host() && due && !self.arena.is_over() && self.session.advance() {
+            self.last_tick = Instant::now();
+        }
+    }
+
+    pub fn notifiy_direction(&mut self, direction: GridVector) {
+        self.direction = Some(direction);
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, _keymods: KeyMods) {
+        match keycode {
+            KeyCode::Escape => self.closed = true,
+            KeyCode::Return if self.session.is_host() && self.arena.is_over() => {
+                self.session.start(random(), self.arena.grid);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        Frame::from_arena(&self.arena).draw(
+            ctx,
+            palette,
+            self.font,
+            Some(self.session.local_id),
+        )?;
+
+        // Draw result
+        let mut lines = vec![];
+        if self.arena.is_over() {
+            lines.push(match self.arena.winner() {
+                Some(id) => format!("{} wins", self.arena.players[id].name),
+                None => "Draw".to_string(),
+            });
+            lines.push(if self.session.is_host() {
+                "Enter for a rematch, Esc to leave".to_string()
+            } else {
+                "Waiting for the host, Esc to leave".to_string()
+            });
+        }
+        if let Some(ref status) = self.status {
+            lines.push(status.clone());
+        }
+        let (width, height) = (window_w(self.arena.grid), window_h(self.arena.grid));
+        let top = height / 2.0 - lines.len() as f32 * FONT_DEFAULT_SIZE;
+        for (i, line) in lines.iter().enumerate() {
+            let mut text = text(line, palette.foreground, self.font);
+            let text_width = text.width(ctx) as f32;
+            text.set_bounds(na::Point2::new(width, height), Align::Center);
+            let dest = na::Point2::new(0.0, top + i as f32 * FONT_DEFAULT_SIZE * 1.6);
+            let background = Rect::new(
+                width / 2.0 - text_width / 2.0 - 12.0,
+                dest.y - 4.0,
+                text_width + 24.0,
+                FONT_DEFAULT_SIZE * 1.4,
+            );
+            let rectangle =
+                Mesh::new_rectangle(ctx, DrawMode::fill(), background, palette.background)?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+            draw(ctx, &text, (dest,))?;
+        }
+
+        Ok(())
+    }
+}
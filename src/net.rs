@@ -0,0 +1,515 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+use base_types::*;
+use constants::*;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum NetEvent {
+    Lobby(Vec<String>),
+    Started { seed: u64, grid: GridVector },
+    Tick(u64, Vec<GridVector>),
+    Left(usize),
+    Closed(String),
+}
+
+// A connection whose lines are read on a background thread
+struct Peer {
+    stream: TcpStream,
+    lines: Receiver<String>,
+    name: Option<String>,
+    open: bool,
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        // A peer that stops reading must not stall the game thread
+        stream.set_write_timeout(Some(NET_WRITE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            while let Some(line) = read_line(&mut reader) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+            let _ = reader.get_ref().shutdown(Shutdown::Both);
+        });
+
+        Ok(Peer {
+            stream,
+            lines,
+            name: None,
+            open: true,
+        })
+    }
+
+    // A failed or timed out write drops the peer, a partial line can't be resumed
+    fn send(&mut self, line: &str) {
+        if !self.open {
+            return;
+        }
+        if writeln!(self.stream, "{}", line).is_err() {
+            self.close();
+        }
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+        // Also ends the reader thread
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    // Marks the peer as closed once its reader thread stopped
+    fn receive(&mut self) -> Vec<String> {
+        let mut lines = vec![];
+        loop {
+            match self.lines.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.open = false;
+                    break;
+                }
+            }
+        }
+        lines
+    }
+}
+
+// Stops at the end of the stream and at lines over NET_MAX_LINE bytes
+fn read_line<R: BufRead>(reader: &mut R) -> Option<String> {
+    let mut line = vec![];
+    reader
+        .by_ref()
+        .take(NET_MAX_LINE as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .ok()?;
+    if line.pop() != Some(b'\n') {
+        return None;
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line).ok()
+}
+
+// Gives up on an unreachable address instead of waiting for the OS to
+pub fn connect(address: &str) -> io::Result<TcpStream> {
+    let socket = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address did not resolve"))?;
+    TcpStream::connect_timeout(&socket, NET_CONNECT_TIMEOUT)
+}
+
+fn parse_direction(dx: Option<&str>, dy: Option<&str>) -> Option<GridVector> {
+    let dx: GridUnit = dx?.parse().ok()?;
+    let dy: GridUnit = dy?.parse().ok()?;
+    if dx.abs() + dy.abs() > 1 {
+        return None;
+    }
+    Some(GridVector::new(dx, dy))
+}
+
+fn clean_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(PROFILE_NAME_MAX_LENGTH)
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        DEFAULT_PROFILE_NAME.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+// Drops the players whose connection closed, the host is always the first name
+fn remove_closed(names: &mut Vec<String>, peers: &mut Vec<Peer>) {
+    let mut kept = names[..1].to_vec();
+    let mut open = vec![];
+    for (peer, name) in peers.drain(..).zip(names.iter().skip(1)) {
+        if peer.open {
+            kept.push(name.clone());
+            open.push(peer);
+        }
+    }
+    *names = kept;
+    *peers = open;
+}
+
+enum Role {
+    Host {
+        listener: TcpListener,
+        // Joined players in order of their player ids, starting at 1
+        peers: Vec<Peer>,
+        pending: Vec<Peer>,
+        inputs: BTreeMap<u64, Vec<Option<GridVector>>>,
+        next_tick: u64,
+    },
+    Guest {
+        host: Peer,
+    },
+}
+
+// One end of a lockstep match. Every peer sends its input for each tick to the
+// host, which relays the complete set once all inputs have arrived.
+pub struct Session {
+    pub names: Vec<String>,
+    pub local_id: usize,
+    pub started: bool,
+    connected: Vec<bool>,
+    role: Role,
+    events: Vec<NetEvent>,
+}
+
+impl Session {
+    pub fn host(address: &str, name: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Session {
+            names: vec![clean_name(name)],
+            local_id: 0,
+            started: false,
+            connected: vec![true],
+            role: Role::Host {
+                listener,
+                peers: vec![],
+                pending: vec![],
+                inputs: BTreeMap::new(),
+                next_tick: 0,
+            },
+            events: vec![],
+        })
+    }
+
+    pub fn join(address: &str, name: &str) -> io::Result<Self> {
+        let stream = connect(address)?;
+        let mut host = Peer::new(stream)?;
+        host.send(&format!("join {}", clean_name(name)));
+
+        Ok(Session {
+            names: vec![],
+            local_id: 0,
+            started: false,
+            connected: vec![],
+            role: Role::Guest { host },
+            events: vec![],
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        match self.role {
+            Role::Host { .. } => true,
+            Role::Guest { .. } => false,
+        }
+    }
+
+    pub fn address(&self) -> Option<String> {
+        match self.role {
+            Role::Host { ref listener, .. } => {
+                listener.local_addr().ok().map(|addr| addr.to_string())
+            }
+            Role::Guest { ref host } => host.stream.peer_addr().ok().map(|addr| addr.to_string()),
+        }
+    }
+
+    fn broadcast(peers: &mut [Peer], line: &str) {
+        for peer in peers.iter_mut().filter(|peer| peer.open) {
+            peer.send(line);
+        }
+    }
+
+    fn lobby_line(names: &[String]) -> String {
+        format!("lobby {}", names.join("\t"))
+    }
+
+    pub fn poll(&mut self) -> Vec<NetEvent> {
+        match self.role {
+            Role::Host { .. } => self.poll_host(),
+            Role::Guest { .. } => self.poll_guest(),
+        }
+        self.events.drain(..).collect()
+    }
+
+    fn poll_host(&mut self) {
+        let started = self.started;
+        let names = &mut self.names;
+        let connected = &mut self.connected;
+        let events = &mut self.events;
+        let (listener, peers, pending, inputs, next_tick) = match self.role {
+            Role::Host {
+                ref listener,
+                ref mut peers,
+                ref mut pending,
+                ref mut inputs,
+                next_tick,
+            } => (listener, peers, pending, inputs, next_tick),
+            Role::Guest { .. } => return,
+        };
+
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(err) = stream
+                        .set_nonblocking(false)
+                        .and_then(|_| Peer::new(stream))
+                        .map(|peer| pending.push(peer))
+                    {
+                        eprintln!("Failed to accept player: {}", err);
+                    }
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    eprintln!("Failed to accept player: {}", err);
+                    break;
+                }
+            }
+        }
+
+        let mut lobby_changed = false;
+        let mut waiting = vec![];
+        for mut peer in pending.drain(..) {
+            for line in peer.receive() {
+                if line.starts_with("join ") && peer.name.is_none() {
+                    peer.name = Some(clean_name(&line["join ".len()..]));
+                }
+            }
+
+            let name = match peer.name.clone() {
+                Some(name) => name,
+                None => {
+                    if peer.open {
+                        waiting.push(peer);
+                    }
+                    continue;
+                }
+            };
+            if started {
+                peer.send("refused the match has already started");
+            } else if names.len() >= MAX_PLAYERS {
+                peer.send("refused the lobby is full");
+            } else {
+                names.push(name);
+                connected.push(true);
+                peers.push(peer);
+                lobby_changed = true;
+            }
+        }
+        *pending = waiting;
+
+        for (index, peer) in peers.iter_mut().enumerate() {
+            let id = index + 1;
+            for line in peer.receive() {
+                let mut parts = line.split(' ');
+                if parts.next() != Some("input") || !started {
+                    continue;
+                }
+                let tick = parts.next().and_then(|tick| tick.parse::<u64>().ok());
+                let direction = parse_direction(parts.next(), parts.next());
+                if let (Some(tick), Some(direction)) = (tick, direction) {
+                    // Past ticks were already relayed and far future ones would pile up
+                    if tick < next_tick || tick > next_tick + NET_MAX_INPUT_LEAD {
+                        continue;
+                    }
+                    let count = connected.len();
+                    inputs.entry(tick).or_insert_with(|| vec![None; count])[id] = Some(direction);
+                }
+            }
+        }
+
+        let mut left = vec![];
+        for (index, peer) in peers.iter().enumerate() {
+            let id = index + 1;
+            if !peer.open && connected[id] {
+                connected[id] = false;
+                left.push(id);
+            }
+        }
+        for id in left {
+            if started {
+                Session::broadcast(peers, &format!("left {}", id));
+                events.push(NetEvent::Left(id));
+            } else {
+                lobby_changed = true;
+            }
+        }
+
+        if !started && lobby_changed {
+            remove_closed(names, peers);
+            *connected = vec![true; names.len()];
+            Session::broadcast(peers, &Session::lobby_line(names));
+            events.push(NetEvent::Lobby(names.clone()));
+        }
+    }
+
+    fn poll_guest(&mut self) {
+        let host = match self.role {
+            Role::Guest { ref mut host } => host,
+            Role::Host { .. } => return,
+        };
+
+        for line in host.receive() {
+            let mut parts = line.splitn(2, ' ');
+            let command = parts.next().unwrap_or("");
+            let rest = parts.next().unwrap_or("");
+            match command {
+                "lobby" => {
+                    self.names = rest.split('\t').map(String::from).collect();
+                    self.connected = vec![true; self.names.len()];
+                    self.events.push(NetEvent::Lobby(self.names.clone()));
+                }
+                "start" => {
+                    let values: Vec<u64> = rest
+                        .split(' ')
+                        .filter_map(|value| value.parse().ok())
+                        .collect();
+                    let in_range =
+                        |value: u64| value >= MIN_GRID_SIZE as u64 && value <= MAX_GRID_SIZE as u64;
+                    let valid = values.len() == 4
+                        && in_range(values[1])
+                        && in_range(values[2])
+                        && values[3] > 0
+                        && (values[3] as usize) < self.names.len();
+                    if !valid {
+                        host.close();
+                        self.events.push(NetEvent::Closed(
+                            "the host sent an invalid start".to_string(),
+                        ));
+                        return;
+                    }
+                    self.started = true;
+                    self.local_id = values[3] as usize;
+                    self.connected = vec![true; self.names.len()];
+                    self.events.push(NetEvent::Started {
+                        seed: values[0],
+                        grid: GridVector::new(values[1] as GridUnit, values[2] as GridUnit),
+                    });
+                }
+                "tick" => {
+                    let mut values = rest.split(' ');
+                    let tick = values.next().and_then(|tick| tick.parse().ok());
+                    let mut directions = vec![];
+                    while let Some(dx) = values.next() {
+                        match parse_direction(Some(dx), values.next()) {
+                            Some(direction) => directions.push(direction),
+                            None => break,
+                        }
+                    }
+                    if let Some(tick) = tick {
+                        self.events.push(NetEvent::Tick(tick, directions));
+                    }
+                }
+                "left" => {
+                    if let Ok(id) = rest.parse::<usize>() {
+                        if let Some(connected) = self.connected.get_mut(id) {
+                            *connected = false;
+                        }
+                        self.events.push(NetEvent::Left(id));
+                    }
+                }
+                "refused" => {
+                    self.events.push(NetEvent::Closed(rest.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        if !host.open {
+            self.events
+                .push(NetEvent::Closed("the host left".to_string()));
+        }
+    }
+
+    // Starts a round for everybody in the lobby, only the host can do this
+    pub fn start(&mut self, seed: u64, grid: GridVector) {
+        if let Role::Host {
+            ref mut peers,
+            ref mut inputs,
+            ref mut next_tick,
+            ..
+        } = self.role
+        {
+            remove_closed(&mut self.names, peers);
+            self.connected = vec![true; self.names.len()];
+            Session::broadcast(peers, &Session::lobby_line(&self.names));
+            for (index, peer) in peers.iter_mut().enumerate() {
+                peer.send(&format!(
+                    "start {} {} {} {}",
+                    seed,
+                    grid.x,
+                    grid.y,
+                    index + 1
+                ));
+            }
+            inputs.clear();
+            *next_tick = 0;
+            self.started = true;
+            self.events.push(NetEvent::Started { seed, grid });
+        }
+    }
+
+    pub fn send_input(&mut self, tick: u64, direction: GridVector) {
+        let local_id = self.local_id;
+        let count = self.connected.len();
+        match self.role {
+            Role::Host { ref mut inputs, .. } => {
+                inputs.entry(tick).or_insert_with(|| vec![None; count])[local_id] = Some(direction);
+            }
+            Role::Guest { ref mut host } => {
+                host.send(&format!("input {} {} {}", tick, direction.x, direction.y));
+            }
+        }
+    }
+
+    // Relays the next tick once every connected player sent its input,
+    // returns whether it did
+    pub fn advance(&mut self) -> bool {
+        let connected = &self.connected;
+        let (peers, inputs, next_tick) = match self.role {
+            Role::Host {
+                ref mut peers,
+                ref mut inputs,
+                ref mut next_tick,
+                ..
+            } => (peers, inputs, next_tick),
+            Role::Guest { .. } => return false,
+        };
+
+        let complete = match inputs.get(next_tick) {
+            Some(directions) => directions
+                .iter()
+                .zip(connected.iter())
+                .all(|(direction, &connected)| direction.is_some() || !connected),
+            None => !connected.iter().any(|&connected| connected),
+        };
+        if !complete {
+            return false;
+        }
+
+        let directions: Vec<GridVector> = inputs
+            .remove(next_tick)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|direction| direction.unwrap_or_else(|| GridVector::new(0, 0)))
+            .chain(::std::iter::repeat(GridVector::new(0, 0)))
+            .take(connected.len())
+            .collect();
+
+        let mut line = format!("tick {}", next_tick);
+        for direction in &directions {
+            line.push_str(&format!(" {} {}", direction.x, direction.y));
+        }
+        Session::broadcast(peers, &line);
+        self.events.push(NetEvent::Tick(*next_tick, directions));
+        *next_tick += 1;
+        true
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use base_types::*;
+use portal::{teleport, Portal};
+use utils::wrap_in_grid;
+
+pub const DIRECTIONS: [GridVector; 4] = [
+    GridVector { x: 0, y: -1 },
+    GridVector { x: 0, y: 1 },
+    GridVector { x: -1, y: 0 },
+    GridVector { x: 1, y: 0 },
+];
+
+pub fn step(
+    grid: GridVector,
+    pos: GridVector,
+    direction: GridVector,
+    portals: &[Portal],
+) -> GridVector {
+    teleport(portals, wrap_in_grid(grid, pos + direction))
+}
+
+pub fn direction_between(
+    grid: GridVector,
+    from: GridVector,
+    to: GridVector,
+    portals: &[Portal],
+) -> Option<GridVector> {
+    DIRECTIONS
+        .iter()
+        .find(|&&direction| step(grid, from, direction, portals) == to)
+        .cloned()
+}
+
+fn index(grid: GridVector, pos: GridVector) -> usize {
+    let pos = wrap_in_grid(grid, pos);
+    pos.y as usize * grid.x as usize + pos.x as usize
+}
+
+pub struct DistanceMap {
+    grid: GridVector,
+    distances: Vec<Option<u32>>,
+}
+
+impl DistanceMap {
+    pub fn new(
+        grid: GridVector,
+        from: GridVector,
+        blocked: &[GridVector],
+        portals: &[Portal],
+    ) -> Self {
+        let size = grid.x as usize * grid.y as usize;
+        let mut distances = vec![None; size];
+        let mut is_blocked = vec![false; size];
+        for pos in blocked {
+            is_blocked[index(grid, *pos)] = true;
+        }
+
+        let mut queue = VecDeque::new();
+        distances[index(grid, from)] = Some(0);
+        queue.push_back((from, 0));
+
+        while let Some((pos, distance)) = queue.pop_front() {
+            for direction in &DIRECTIONS {
+                let next = step(grid, pos, *direction, portals);
+                let i = index(grid, next);
+                if distances[i].is_none() && !is_blocked[i] {
+                    distances[i] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        DistanceMap { grid, distances }
+    }
+
+    pub fn get(&self, pos: GridVector) -> Option<u32> {
+        self.distances[index(self.grid, pos)]
+    }
+}
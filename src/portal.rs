@@ -0,0 +1,31 @@
+use base_types::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Portal {
+    pub a: GridVector,
+    pub b: GridVector,
+}
+
+impl Portal {
+    pub fn new(a: GridVector, b: GridVector) -> Self {
+        Portal { a, b }
+    }
+
+    pub fn exit_for(&self, pos: GridVector) -> Option<GridVector> {
+        if pos == self.a {
+            Some(self.b)
+        } else if pos == self.b {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn teleport(portals: &[Portal], pos: GridVector) -> GridVector {
+    portals
+        .iter()
+        .filter_map(|portal| portal.exit_for(pos))
+        .next()
+        .unwrap_or(pos)
+}
@@ -0,0 +1,68 @@
+use base_types::*;
+use constants::*;
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum PowerUpKind {
+    Ghost,
+    Shield,
+    Magnet,
+    Multiplier,
+}
+
+impl PowerUpKind {
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        match rng.gen::<u8>() % 4 {
+            0 => PowerUpKind::Ghost,
+            1 => PowerUpKind::Shield,
+            2 => PowerUpKind::Magnet,
+            _ => PowerUpKind::Multiplier,
+        }
+    }
+
+    pub fn duration(self) -> f64 {
+        match self {
+            PowerUpKind::Ghost => GHOST_DURATION,
+            PowerUpKind::Shield => SHIELD_DURATION,
+            PowerUpKind::Magnet => MAGNET_DURATION,
+            PowerUpKind::Multiplier => MULTIPLIER_DURATION,
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            PowerUpKind::Ghost => "G",
+            PowerUpKind::Shield => "S",
+            PowerUpKind::Magnet => "M",
+            PowerUpKind::Multiplier => "x2",
+        }
+    }
+}
+
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub position: GridVector,
+    pub spawned_at: f64,
+}
+
+impl PowerUp {
+    pub fn new(kind: PowerUpKind, pos: GridVector, time: f64) -> Self {
+        PowerUp {
+            kind,
+            position: pos,
+            spawned_at: time,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    pub activated_at: f64,
+}
+
+impl ActivePowerUp {
+    pub fn remaining(&self, time: f64) -> f64 {
+        (1.0 - (time - self.activated_at) / self.kind.duration()).max(0.0)
+    }
+}
@@ -0,0 +1,224 @@
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+
+use ggez::{filesystem, Context, GameError, GameResult};
+
+use achievements::Achievements;
+use constants::*;
+use game_mode::GameMode;
+use high_scores::HighScores;
+use settings::Settings;
+use stats::Stats;
+
+pub struct Profile {
+    pub name: String,
+    pub settings: Settings,
+    pub stats: Stats,
+    pub achievements: Achievements,
+    pub high_scores: Vec<HighScores>,
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name.chars().count() <= PROFILE_NAME_MAX_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+fn profile_path(name: &str) -> String {
+    let file_name: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect();
+    format!("{}/{}.txt", PROFILES_DIR, file_name)
+}
+
+fn read_file(ctx: &mut Context, path: &str) -> GameResult<String> {
+    let mut file = filesystem::open(ctx, path)?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn write_file(ctx: &mut Context, path: &str, content: &str) -> GameResult<()> {
+    let mut file = filesystem::create(ctx, path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.trim().to_string(),
+            settings: Settings::default(),
+            stats: Stats::default(),
+            achievements: Achievements::parse(""),
+            high_scores: GameMode::all()
+                .iter()
+                .map(|&mode| HighScores::parse(mode, ""))
+                .collect(),
+        }
+    }
+
+    pub fn high_scores(&self, mode: GameMode) -> &HighScores {
+        self.high_scores
+            .iter()
+            .find(|scores| scores.mode == mode)
+            .expect("every mode has a high score table")
+    }
+
+    pub fn high_scores_mut(&mut self, mode: GameMode) -> &mut HighScores {
+        self.high_scores
+            .iter_mut()
+            .find(|scores| scores.mode == mode)
+            .expect("every mode has a high score table")
+    }
+
+    pub fn parse(source: &str) -> Self {
+        let mut name = String::new();
+        let mut sections: Vec<(String, String)> = vec![];
+
+        for line in source.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                let section = trimmed[1..trimmed.len() - 1].trim().to_string();
+                sections.push((section, String::new()));
+            } else if let Some(&mut (ref section, ref mut content)) = sections.last_mut() {
+                if section == "profile" {
+                    let mut parts = trimmed.splitn(2, '=');
+                    if parts.next().map(str::trim) == Some("name") {
+                        name = parts.next().unwrap_or("").trim().to_string();
+                    }
+                } else {
+                    content.push_str(line);
+                    content.push('\n');
+                }
+            }
+        }
+
+        let mut profile = Profile::new(&name);
+        for (section, content) in sections {
+            match section.as_str() {
+                "settings" => profile.settings = Settings::parse(&content),
+                "stats" => profile.stats = Stats::parse(&content),
+                "achievements" => profile.achievements = Achievements::parse(&content),
+                _ => {
+                    let mode = GameMode::all()
+                        .iter()
+                        .cloned()
+                        .find(|mode| section == format!("high-scores {}", mode.id()));
+                    if let Some(mode) = mode {
+                        *profile.high_scores_mut(mode) = HighScores::parse(mode, &content);
+                    }
+                }
+            }
+        }
+
+        profile
+    }
+
+    pub fn to_source(&self) -> String {
+        let mut source = format!("[profile]\nname = {}\n", self.name);
+        source += &format!("\n[settings]\n{}", self.settings.to_source());
+        source += &format!("\n[stats]\n{}", self.stats.to_source());
+        source += &format!("\n[achievements]\n{}", self.achievements.to_source());
+        for scores in &self.high_scores {
+            source += &format!(
+                "\n[high-scores {}]\n{}",
+                scores.mode.id(),
+                scores.to_source()
+            );
+        }
+        source
+    }
+
+    pub fn load(ctx: &mut Context, name: &str) -> GameResult<Self> {
+        let profile = Profile::parse(&read_file(ctx, &profile_path(name))?);
+        if profile.name.is_empty() {
+            return Err(GameError::ResourceLoadError(format!(
+                "{}: missing profile name",
+                profile_path(name)
+            )));
+        }
+        Ok(profile)
+    }
+
+    pub fn save(&self, ctx: &mut Context) -> GameResult<()> {
+        if !filesystem::is_dir(ctx, PROFILES_DIR) {
+            filesystem::create_dir(ctx, PROFILES_DIR)?;
+        }
+        write_file(ctx, &profile_path(&self.name), &self.to_source())
+    }
+
+    pub fn delete(ctx: &mut Context, name: &str) -> GameResult<()> {
+        filesystem::delete(ctx, profile_path(name))
+    }
+
+    pub fn exists(ctx: &Context, name: &str) -> bool {
+        filesystem::is_file(ctx, profile_path(name))
+    }
+
+    pub fn list(ctx: &mut Context) -> Vec<String> {
+        let mut paths: Vec<_> = match filesystem::read_dir(ctx, PROFILES_DIR) {
+            Ok(paths) => paths
+                .filter(|path| path.extension() == Some(OsStr::new("txt")))
+                .collect(),
+            Err(_) => vec![],
+        };
+        paths.sort();
+
+        let mut names = vec![];
+        for path in paths {
+            match read_file(ctx, &path.to_string_lossy()) {
+                Ok(source) => {
+                    let profile = Profile::parse(&source);
+                    if !profile.name.is_empty() {
+                        names.push(profile.name);
+                    }
+                }
+                Err(err) => eprintln!("Failed to read profile: {}", err),
+            }
+        }
+        names
+    }
+
+    // Builds the first profile from the files written before profiles existed
+    fn migrate(ctx: &mut Context) -> Self {
+        let mut profile = Profile::new(DEFAULT_PROFILE_NAME);
+        profile.settings = Settings::load(ctx);
+        profile.stats = Stats::load(ctx);
+        profile.achievements = Achievements::load(ctx);
+        profile.high_scores = GameMode::all()
+            .iter()
+            .map(|&mode| HighScores::load(ctx, mode))
+            .collect();
+        profile
+    }
+
+    pub fn load_active(ctx: &mut Context) -> Self {
+        let active = match read_file(ctx, ACTIVE_PROFILE_PATH) {
+            Ok(ref name) if Profile::exists(ctx, name.trim()) => Some(name.trim().to_string()),
+            _ => Profile::list(ctx).into_iter().next(),
+        };
+
+        if let Some(name) = active {
+            match Profile::load(ctx, &name) {
+                Ok(profile) => return profile,
+                Err(err) => eprintln!("Failed to load profile: {}", err),
+            }
+        }
+
+        let profile = Profile::migrate(ctx);
+        if let Err(err) = profile.save(ctx) {
+            eprintln!("Failed to save profile: {}", err);
+        }
+        profile
+    }
+
+    pub fn set_active(ctx: &mut Context, name: &str) -> GameResult<()> {
+        write_file(ctx, ACTIVE_PROFILE_PATH, &format!("{}\n", name))
+    }
+}
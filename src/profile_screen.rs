@@ -0,0 +1,211 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{Font, Rect};
+use ggez::{Context, GameResult};
+
+use constants::*;
+use profile::{is_valid_name, Profile};
+use theme::Palette;
+use ui::{Button, Center, Label, List, Message, Stack, TextInput, Ui, UiEvent};
+
+const COLUMN_WIDTH: f32 = 260.0;
+const LIST_ROWS: usize = 8;
+
+fn button(text: &str, id: &'static str, font: Font) -> Button {
+    Button::new(text, 36.0, font, Rect::new(0.0, 0.0, COLUMN_WIDTH, 36.0)).with_id(id)
+}
+
+fn build_ui(
+    names: &[String],
+    selected: Option<usize>,
+    active: &str,
+    new_name: &str,
+    status: &str,
+    area: Rect,
+    font: Font,
+) -> Ui {
+    let mut list = List::new("profiles", names.to_vec(), font, COLUMN_WIDTH, LIST_ROWS);
+    if let Some(index) = selected {
+        list.select(index);
+    }
+
+    let actions = Stack::vertical(6.0)
+        .with(Label::new(
+            &format!("Active: {}", active),
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(button("Switch", "switch", font))
+        .with(button("Delete", "delete", font))
+        .with(Label::new("New profile", font, COLUMN_WIDTH))
+        .with(TextInput::new(
+            "name",
+            new_name,
+            PROFILE_NAME_MAX_LENGTH,
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(button("Create", "create", font))
+        .with(button("Back", "back", font));
+
+    let root = Stack::vertical(16.0)
+        .with(Label::new("Profiles", font, 2.0 * COLUMN_WIDTH))
+        .with(Stack::horizontal(30.0).with(list).with(actions))
+        .with(Label::new(status, font, 2.0 * COLUMN_WIDTH + 30.0));
+
+    Ui::new(Center::new(root), area)
+}
+
+pub struct ProfileScreen {
+    pub switch_to: Option<String>,
+    pub closed: bool,
+    names: Vec<String>,
+    selected: Option<usize>,
+    active: String,
+    new_name: String,
+    status: String,
+    ui: Ui,
+    area: Rect,
+    font: Font,
+}
+
+impl ProfileScreen {
+    pub fn new(ctx: &mut Context, active: &str, area: Rect, font: Font) -> Self {
+        let mut screen = ProfileScreen {
+            switch_to: None,
+            closed: false,
+            names: vec![],
+            selected: None,
+            active: active.to_string(),
+            new_name: String::new(),
+            status: String::new(),
+            ui: Ui::new(Stack::vertical(0.0), area),
+            area,
+            font,
+        };
+        screen.refresh(ctx);
+        screen.ui.set_focus(Some(0));
+        screen
+    }
+
+    pub fn set_active(&mut self, active: &str) {
+        self.active = active.to_string();
+        self.status = format!("Switched to {}", active);
+        self.rebuild();
+    }
+
+    fn refresh(&mut self, ctx: &mut Context) {
+        self.names = Profile::list(ctx);
+        self.selected = self.names.iter().position(|name| *name == self.active);
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        let focus = self.ui.focus();
+        self.ui = build_ui(
+            &self.names,
+            self.selected,
+            &self.active,
+            &self.new_name,
+            &self.status,
+            self.area,
+            self.font,
+        );
+        self.ui.set_focus(focus);
+    }
+
+    fn create(&mut self, ctx: &mut Context) {
+        let name = self.new_name.trim().to_string();
+        if !is_valid_name(&name) {
+            self.status = "Use letters, numbers, spaces, - and _".to_string();
+        } else if Profile::exists(ctx, &name) {
+            self.status = format!("{} already exists", name);
+        } else {
+            match Profile::new(&name).save(ctx) {
+                Ok(()) => {
+                    self.status = format!("Created {}", name);
+                    self.new_name.clear();
+                    self.names = Profile::list(ctx);
+                    self.selected = self.names.iter().position(|other| *other == name);
+                }
+                Err(err) => {
+                    eprintln!("Failed to save profile: {}", err);
+                    self.status = "Failed to create profile".to_string();
+                }
+            }
+        }
+        self.rebuild();
+    }
+
+    fn delete(&mut self, ctx: &mut Context) {
+        let name = match self.selected {
+            Some(index) => self.names[index].clone(),
+            None => return,
+        };
+
+        if name == self.active {
+            self.status = "Switch to another profile before deleting this one".to_string();
+            self.rebuild();
+            return;
+        }
+
+        match Profile::delete(ctx, &name) {
+            Ok(()) => self.status = format!("Deleted {}", name),
+            Err(err) => {
+                eprintln!("Failed to delete profile: {}", err);
+                self.status = "Failed to delete profile".to_string();
+            }
+        }
+        self.refresh(ctx);
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, area: Rect) {
+        if self.area != area {
+            self.area = area;
+            self.rebuild();
+        }
+
+        for message in self.ui.poll() {
+            match message {
+                Message::Selected("profiles", index) => self.selected = Some(index),
+                Message::TextChanged("name", text) => self.new_name = text,
+                Message::Submitted("name", _) | Message::Clicked("create") => self.create(ctx),
+                Message::Clicked("switch") => {
+                    if let Some(index) = self.selected {
+                        self.switch_to = Some(self.names[index].clone());
+                    }
+                }
+                Message::Clicked("delete") => self.delete(ctx),
+                Message::Clicked("back") => self.closed = true,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.ui.draw(ctx, palette)
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        if keycode == KeyCode::Escape {
+            self.closed = true;
+        } else {
+            self.ui.handle(UiEvent::Key(keycode, keymods));
+        }
+    }
+
+    pub fn notifiy_text(&mut self, character: char) {
+        self.ui.handle(UiEvent::Text(character));
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseDown(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseUp(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseMotion(x, y));
+    }
+}
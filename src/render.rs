@@ -0,0 +1,305 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use ggez::graphics::{Color, Rect};
+use png;
+use rusttype::{point, Font, Scale};
+
+use assets::BUILT_IN_FONT;
+use constants::*;
+use game::Game;
+use grid::{window_h, window_w};
+use power_up::PowerUpKind;
+use theme::Palette;
+use utils::*;
+
+// An RGBA image in memory, drawn without a window or a GPU
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pixmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Pixmap {
+    pub fn new(width: u32, height: u32, color: Color) -> Self {
+        let (r, g, b, a) = color.to_rgba();
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[r, g, b, a]);
+        }
+        Pixmap {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Blends `color` over the pixel, `coverage` scales its alpha
+    pub fn blend(&mut self, x: i32, y: i32, color: Color, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+
+        let alpha = color.a * coverage;
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.pixels[index..index + 4];
+        for (channel, value) in pixel.iter_mut().zip(&[color.r, color.g, color.b]) {
+            let mixed = f32::from(*channel) / 255.0 * (1.0 - alpha) + value * alpha;
+            *channel = (mixed * 255.0).round() as u8;
+        }
+        let mixed = f32::from(pixel[3]) / 255.0 * (1.0 - alpha) + alpha;
+        pixel[3] = (mixed * 255.0).round() as u8;
+    }
+
+    // Fills every pixel whose center lies inside the rectangle
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let left = (rect.x - 0.5).ceil() as i32;
+        let top = (rect.y - 0.5).ceil() as i32;
+        let right = (rect.x + rect.w - 0.5).ceil() as i32;
+        let bottom = (rect.y + rect.h - 0.5).ceil() as i32;
+        for y in top..bottom {
+            for x in left..right {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    // Like a stroked mesh the line is centered on the outline
+    pub fn stroke_rect(&mut self, rect: Rect, line_width: f32, color: Color) {
+        let half = line_width / 2.0;
+        let outer = scale_rect(rect, half);
+        self.fill_rect(Rect::new(outer.x, outer.y, outer.w, line_width), color);
+        self.fill_rect(
+            Rect::new(outer.x, rect.y + rect.h - half, outer.w, line_width),
+            color,
+        );
+        self.fill_rect(
+            Rect::new(outer.x, rect.y + half, line_width, rect.h - line_width),
+            color,
+        );
+        self.fill_rect(
+            Rect::new(
+                rect.x + rect.w - half,
+                rect.y + half,
+                line_width,
+                rect.h - line_width,
+            ),
+            color,
+        );
+    }
+
+    pub fn stroke_circle(&mut self, x: f32, y: f32, radius: f32, line_width: f32, color: Color) {
+        let reach = (radius + line_width).ceil() as i32;
+        for py in y as i32 - reach..=y as i32 + reach {
+            for px in x as i32 - reach..=x as i32 + reach {
+                let dx = px as f32 + 0.5 - x;
+                let dy = py as f32 + 0.5 - y;
+                let distance = (dx * dx + dy * dy).sqrt();
+                if (distance - radius).abs() <= line_width / 2.0 {
+                    self.blend(px, py, color, 1.0);
+                }
+            }
+        }
+    }
+
+    // Every pixel repeated `factor` times in both directions
+    pub fn scaled(&self, factor: u32) -> Self {
+        if factor <= 1 {
+            return self.clone();
+        }
+
+        let width = self.width * factor;
+        let mut pixels = Vec::with_capacity(self.pixels.len() * (factor * factor) as usize);
+        for row in self.pixels.chunks(self.width as usize * 4) {
+            let mut line = Vec::with_capacity(width as usize * 4);
+            for pixel in row.chunks(4) {
+                for _ in 0..factor {
+                    line.extend_from_slice(pixel);
+                }
+            }
+            for _ in 0..factor {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        Pixmap {
+            width,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    // Only reads 8-bit RGBA images like the ones `save_png` writes
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let decoder = png::Decoder::new(File::open(path)?);
+        let (info, mut reader) = decoder.read_info()?;
+        if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected an 8-bit RGBA image",
+            ));
+        }
+
+        let mut pixels = vec![0; info.buffer_size()];
+        reader.next_frame(&mut pixels)?;
+        Ok(Pixmap {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+}
+
+// Draws a game the way the window does, minus animations and overlays
+pub struct Renderer {
+    font: Font<'static>,
+    palette: Palette,
+}
+
+impl Renderer {
+    pub fn new(palette: Palette) -> Self {
+        let font = Font::from_bytes(BUILT_IN_FONT).expect("the built-in font is valid");
+        Renderer { font, palette }
+    }
+
+    pub fn text_width(&self, content: &str) -> f32 {
+        let scale = Scale::uniform(FONT_DEFAULT_SIZE);
+        self.font
+            .layout(content, scale, point(0.0, 0.0))
+            .last()
+            .map(|glyph| glyph.position().x + glyph.unpositioned().h_metrics().advance_width)
+            .unwrap_or(0.0)
+    }
+
+    // `x` and `y` are the top left corner of the line, as with a drawn `Text`
+    pub fn text(&self, pixmap: &mut Pixmap, content: &str, x: f32, y: f32, color: Color) {
+        let scale = Scale::uniform(FONT_DEFAULT_SIZE);
+        let ascent = self.font.v_metrics(scale).ascent;
+        for glyph in self.font.layout(content, scale, point(x, y + ascent)) {
+            if let Some(bounds) = glyph.pixel_bounding_box() {
+                glyph.draw(|gx, gy, coverage| {
+                    let px = bounds.min.x + gx as i32;
+                    let py = bounds.min.y + gy as i32;
+                    pixmap.blend(px, py, color, coverage);
+                });
+            }
+        }
+    }
+
+    pub fn render(&self, game: &Game, name: &str) -> Pixmap {
+        let width = window_w(game.grid);
+        let height = window_h(game.grid);
+        let background = self.palette.background;
+        let foreground = self.palette.foreground;
+        let mut pixmap = Pixmap::new(width as u32, height as u32, background);
+        let score = game.snake.score;
+
+        // Draw apple
+        pixmap.fill_rect(gv_to_rect(&game.apple.position), foreground);
+
+        // Draw power-up
+        if let Some(ref power_up) = game.power_up {
+            pixmap.stroke_rect(
+                scale_rect(gv_to_rect(&power_up.position), -1.0),
+                2.0,
+                foreground,
+            );
+        }
+
+        // Draw snake
+        let snake = &game.snake;
+        for segment in &snake.tail {
+            pixmap.fill_rect(gv_to_rect(segment), foreground);
+        }
+        if snake.has_power_up(PowerUpKind::Ghost) {
+            for segment in &snake.tail {
+                pixmap.fill_rect(scale_rect(gv_to_rect(segment), -3.0), background);
+            }
+        }
+        let head = gv_to_rect(&snake.head());
+        pixmap.fill_rect(scale_rect(head, -2.0), background);
+        if snake.has_power_up(PowerUpKind::Shield) {
+            pixmap.stroke_rect(scale_rect(head, 3.0), 1.0, foreground);
+        }
+
+        // Draw portals
+        for portal in &game.level.portals {
+            for pos in &[portal.a, portal.b] {
+                let rect = gv_to_rect(pos);
+                pixmap.stroke_circle(
+                    rect.x + rect.w / 2.0,
+                    rect.y + rect.h / 2.0,
+                    rect.w / 2.0 - 1.0,
+                    2.0,
+                    foreground,
+                );
+            }
+        }
+
+        // Draw stones
+        for stone in game.stones.iter().filter(|stone| stone.is_spawned(score)) {
+            if stone.is_active(game.time, score) {
+                pixmap.fill_rect(gv_to_rect(&stone.pos), foreground);
+            } else {
+                pixmap.stroke_rect(scale_rect(gv_to_rect(&stone.pos), -3.0), 1.0, foreground);
+            }
+        }
+
+        // Draw info bar
+        let bar = Rect::new(0.0, 0.0, width, INFO_BAR_HIGHT);
+        pixmap.fill_rect(bar, foreground);
+        pixmap.stroke_rect(scale_rect(bar, -2.0), 2.0, background);
+
+        let y = (INFO_BAR_HIGHT - FONT_DEFAULT_SIZE) / 2.0 - 4.0;
+        let score_text = format!("Score: {}", score);
+        self.text(&mut pixmap, &score_text, 12.0, y, background);
+        let mut x = 12.0 + self.text_width(&score_text) + 30.0;
+        self.text(&mut pixmap, name, x, y, background);
+        x += self.text_width(name) + 30.0;
+
+        for power_up in &snake.power_ups {
+            let icon = power_up.kind.icon();
+            self.text(&mut pixmap, icon, x, y, background);
+            x += self.text_width(icon) + 6.0;
+
+            let bar = Rect::new(x, INFO_BAR_HIGHT / 2.0 - 5.0, POWER_UP_BAR_WIDTH, 10.0);
+            pixmap.stroke_rect(bar, 1.0, background);
+            let remaining = power_up.remaining(game.time) as f32;
+            if remaining > 0.0 {
+                let fill = Rect::new(bar.x, bar.y, bar.w * remaining, bar.h);
+                pixmap.fill_rect(fill, background);
+            }
+            x += POWER_UP_BAR_WIDTH + 24.0;
+        }
+
+        let status = match game.config.time_limit {
+            Some(limit) => {
+                let remaining = (limit - game.time).max(0.0).ceil() as u32;
+                format!(
+                    "{}  {}:{:02}",
+                    game.mode.name(),
+                    remaining / 60,
+                    remaining % 60
+                )
+            }
+            None => game.mode.name().to_string(),
+        };
+        let x = width - 12.0 - self.text_width(&status);
+        self.text(&mut pixmap, &status, x, y, background);
+
+        pixmap
+    }
+}
@@ -0,0 +1,265 @@
+use ggez::{GameError, GameResult};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use base_types::*;
+use constants::*;
+use game::Game;
+use game_mode::GameMode;
+use level::Level;
+use pathfinding::DIRECTIONS;
+
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub speed: f64,
+    pub grid: GridVector,
+    pub level: Level,
+    // Sorted by tick
+    pub turns: Vec<(u64, GridVector)>,
+    pub ticks: u64,
+    pub score: u32,
+}
+
+fn parse_error(line_number: usize, message: &str) -> GameError {
+    GameError::ResourceLoadError(format!("line {}: {}", line_number + 1, message))
+}
+
+fn parse_number<T: ::std::str::FromStr>(line_number: usize, value: &str) -> GameResult<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| parse_error(line_number, &format!("invalid number `{}`", value)))
+}
+
+fn parse_args(line_number: usize, args: &[&str], count: usize) -> GameResult<()> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(parse_error(
+            line_number,
+            &format!("expected {} arguments, found {}", count, args.len()),
+        ))
+    }
+}
+
+impl Replay {
+    pub fn record(game: &Game) -> Self {
+        Replay {
+            seed: game.seed,
+            mode: game.mode,
+            speed: game.initial_speed,
+            grid: game.grid,
+            level: game.level.clone(),
+            turns: game.turns.clone(),
+            ticks: game.tick,
+            score: game.snake.score,
+        }
+    }
+
+    pub fn start(&self) -> Game {
+        Game::new(
+            self.grid,
+            self.level.clone(),
+            self.mode,
+            self.speed,
+            self.seed,
+        )
+    }
+
+    pub fn directions(&self, tick: u64) -> Vec<GridVector> {
+        // Never equal, so the search ends on the first turn of the tick
+        let first = match self.turns.binary_search_by(|&(turn_tick, _)| {
+            if turn_tick < tick {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }) {
+            Ok(index) | Err(index) => index,
+        };
+        self.turns[first..]
+            .iter()
+            .take_while(|&&(turn_tick, _)| turn_tick == tick)
+            .map(|&(_, direction)| direction)
+            .collect()
+    }
+
+    pub fn simulate(&self) -> Game {
+        self.simulate_to(self.ticks)
+    }
+
+    // Plays the recorded turns up to the given tick, or until the recording ends
+    pub fn simulate_to(&self, tick: u64) -> Game {
+        let mut game = self.start();
+        while game.tick < tick.min(self.ticks) && !game.is_over() {
+            let directions = self.directions(game.tick);
+            game.step(&directions);
+            if !game.is_started() {
+                break;
+            }
+        }
+        game
+    }
+
+    pub fn matches(&self, game: &Game) -> bool {
+        game.tick == self.ticks && game.snake.score == self.score
+    }
+
+    pub fn parse(source: &str) -> GameResult<Self> {
+        let mut seed = None;
+        let mut mode = None;
+        let mut speed = None;
+        let mut grid = None;
+        let mut turns = vec![];
+        let mut ticks = 0;
+        let mut score = 0;
+        let mut level = None;
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap_or("");
+            let args: Vec<&str> = words.collect();
+
+            match command {
+                "seed" => {
+                    parse_args(line_number, &args, 1)?;
+                    seed = Some(parse_number(line_number, args[0])?);
+                }
+                "mode" => {
+                    parse_args(line_number, &args, 1)?;
+                    let found = GameMode::all().iter().cloned().find(|m| m.id() == args[0]);
+                    match found {
+                        Some(found) => mode = Some(found),
+                        None => {
+                            return Err(parse_error(
+                                line_number,
+                                &format!("unknown mode `{}`", args[0]),
+                            ))
+                        }
+                    }
+                }
+                "speed" => {
+                    parse_args(line_number, &args, 1)?;
+                    speed = Some(parse_number(line_number, args[0])?);
+                }
+                "grid" => {
+                    parse_args(line_number, &args, 2)?;
+                    let size = GridVector::new(
+                        parse_number(line_number, args[0])?,
+                        parse_number(line_number, args[1])?,
+                    );
+                    let valid = MIN_GRID_SIZE..=MAX_GRID_SIZE;
+                    if !valid.contains(&size.x) || !valid.contains(&size.y) {
+                        return Err(parse_error(line_number, "grid size out of range"));
+                    }
+                    grid = Some(size);
+                }
+                "ticks" => {
+                    parse_args(line_number, &args, 1)?;
+                    ticks = parse_number(line_number, args[0])?;
+                    if ticks > MAX_REPLAY_TICKS {
+                        return Err(parse_error(line_number, "too many ticks"));
+                    }
+                }
+                "score" => {
+                    parse_args(line_number, &args, 1)?;
+                    score = parse_number(line_number, args[0])?;
+                }
+                "turn" => {
+                    parse_args(line_number, &args, 3)?;
+                    let direction = GridVector::new(
+                        parse_number(line_number, args[1])?,
+                        parse_number(line_number, args[2])?,
+                    );
+                    if !DIRECTIONS.contains(&direction) {
+                        return Err(parse_error(line_number, "invalid turn direction"));
+                    }
+                    turns.push((parse_number(line_number, args[0])?, direction));
+                }
+                "level" => {
+                    // The level has to fit the grid, so that comes first
+                    let size = grid.ok_or_else(|| parse_error(line_number, "missing `grid`"))?;
+                    let rest: Vec<&str> = source.lines().skip(line_number + 1).collect();
+                    let parsed =
+                        Level::parse("Replay", &rest.join("\n"), size).map_err(
+                            |err| match err {
+                                GameError::ResourceLoadError(message) => {
+                                    GameError::ResourceLoadError(format!("level: {}", message))
+                                }
+                                err => err,
+                            },
+                        )?;
+                    level = Some(parsed);
+                    break;
+                }
+                _ => {
+                    return Err(parse_error(
+                        line_number,
+                        &format!("unknown command `{}`", command),
+                    ))
+                }
+            }
+        }
+
+        // A game turns at most once per tick
+        if turns.len() as u64 > ticks {
+            return Err(GameError::ResourceLoadError(
+                "more turns than ticks".to_string(),
+            ));
+        }
+        turns.sort_by_key(|&(tick, _)| tick);
+
+        let missing = |name: &str| GameError::ResourceLoadError(format!("missing `{}`", name));
+        Ok(Replay {
+            seed: seed.ok_or_else(|| missing("seed"))?,
+            mode: mode.ok_or_else(|| missing("mode"))?,
+            speed: speed.ok_or_else(|| missing("speed"))?,
+            grid: grid.ok_or_else(|| missing("grid"))?,
+            level: level.ok_or_else(|| missing("level"))?,
+            turns,
+            ticks,
+            score,
+        })
+    }
+
+    pub fn to_source(&self) -> String {
+        let mut lines = vec![
+            format!("seed {}", self.seed),
+            format!("mode {}", self.mode.id()),
+            format!("speed {}", self.speed),
+            format!("grid {} {}", self.grid.x, self.grid.y),
+            format!("ticks {}", self.ticks),
+            format!("score {}", self.score),
+        ];
+        for &(tick, direction) in &self.turns {
+            lines.push(format!("turn {} {} {}", tick, direction.x, direction.y));
+        }
+        lines.push("level".to_string());
+
+        lines.join("\n") + "\n" + &self.level.to_source()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> GameResult<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+
+        Replay::parse(&source).map_err(|err| match err {
+            GameError::ResourceLoadError(message) => {
+                GameError::ResourceLoadError(format!("{}: {}", path.display(), message))
+            }
+            err => err,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> GameResult<()> {
+        fs::write(path, self.to_source())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use ggez::event::KeyCode;
+use ggez::{filesystem, Context};
+
+use base_types::*;
+use constants::*;
+use theme::Theme;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SpeedPreset {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl SpeedPreset {
+    pub fn all() -> [SpeedPreset; 3] {
+        [SpeedPreset::Slow, SpeedPreset::Normal, SpeedPreset::Fast]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SpeedPreset::Slow => "Slow",
+            SpeedPreset::Normal => "Normal",
+            SpeedPreset::Fast => "Fast",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            SpeedPreset::Slow => "slow",
+            SpeedPreset::Normal => "normal",
+            SpeedPreset::Fast => "fast",
+        }
+    }
+
+    pub fn initial_speed(self) -> f64 {
+        match self {
+            SpeedPreset::Slow => INITIAL_SPEED * 1.5,
+            SpeedPreset::Normal => INITIAL_SPEED,
+            SpeedPreset::Fast => INITIAL_SPEED * 0.7,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum GridSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl GridSize {
+    pub fn all() -> [GridSize; 3] {
+        [GridSize::Small, GridSize::Medium, GridSize::Large]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GridSize::Small => "Small",
+            GridSize::Medium => "Medium",
+            GridSize::Large => "Large",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            GridSize::Small => "small",
+            GridSize::Medium => "medium",
+            GridSize::Large => "large",
+        }
+    }
+
+    pub fn size(self) -> GridVector {
+        match self {
+            GridSize::Small => GridVector::new(40, 26),
+            GridSize::Medium => DEFAULT_GRID_SIZE,
+            GridSize::Large => GridVector::new(64, 40),
+        }
+    }
+}
+
+pub const BINDABLE_KEYS: [KeyCode; 30] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+];
+
+pub fn key_name(keycode: KeyCode) -> String {
+    format!("{:?}", keycode)
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .cloned()
+        .find(|&keycode| key_name(keycode) == name)
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct KeyBindings {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            up: KeyCode::W,
+            down: KeyCode::S,
+            left: KeyCode::A,
+            right: KeyCode::D,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Settings {
+    pub speed: SpeedPreset,
+    pub grid_size: GridSize,
+    pub theme: Theme,
+    pub volume: f32,
+    pub key_bindings: KeyBindings,
+    pub grid_lines: bool,
+    pub smooth_movement: bool,
+    pub fullscreen: bool,
+    pub effects: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            speed: SpeedPreset::Normal,
+            grid_size: GridSize::Medium,
+            theme: Theme::Dark,
+            volume: DEFAULT_VOLUME,
+            key_bindings: KeyBindings::default(),
+            grid_lines: false,
+            smooth_movement: SMOOTH_MOVEMENT,
+            fullscreen: false,
+            effects: EFFECTS_ENABLED,
+        }
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn settings_path(ctx: &Context) -> PathBuf {
+    filesystem::user_config_dir(ctx).join(SETTINGS_FILE)
+}
+
+impl Settings {
+    pub fn parse(source: &str) -> Self {
+        let mut settings = Settings::default();
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts.next().unwrap_or("").trim();
+
+            let valid = match key {
+                "speed" => SpeedPreset::all()
+                    .iter()
+                    .find(|preset| preset.id() == value)
+                    .map(|&preset| settings.speed = preset),
+                "grid" => GridSize::all()
+                    .iter()
+                    .find(|size| size.id() == value)
+                    .map(|&size| settings.grid_size = size),
+                "theme" => Theme::all(true)
+                    .iter()
+                    .find(|theme| theme.id() == value)
+                    .map(|&theme| settings.theme = theme),
+                "volume" => value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|volume| *volume >= 0.0 && *volume <= 1.0)
+                    .map(|volume| settings.volume = volume),
+                "grid_lines" => parse_bool(value).map(|on| settings.grid_lines = on),
+                "interpolation" => parse_bool(value).map(|on| settings.smooth_movement = on),
+                "fullscreen" => parse_bool(value).map(|on| settings.fullscreen = on),
+                "effects" => parse_bool(value).map(|on| settings.effects = on),
+                "key_up" => parse_key(value).map(|key| settings.key_bindings.up = key),
+                "key_down" => parse_key(value).map(|key| settings.key_bindings.down = key),
+                "key_left" => parse_key(value).map(|key| settings.key_bindings.left = key),
+                "key_right" => parse_key(value).map(|key| settings.key_bindings.right = key),
+                _ => None,
+            };
+
+            if valid.is_none() {
+                eprintln!(
+                    "Ignoring invalid setting on line {}: `{}`",
+                    line_number + 1,
+                    line
+                );
+            }
+        }
+
+        settings
+    }
+
+    pub fn to_source(&self) -> String {
+        let bindings = &self.key_bindings;
+        let lines = vec![
+            format!("speed = {}", self.speed.id()),
+            format!("grid = {}", self.grid_size.id()),
+            format!("theme = {}", self.theme.id()),
+            format!("volume = {}", self.volume),
+            format!("grid_lines = {}", self.grid_lines),
+            format!("interpolation = {}", self.smooth_movement),
+            format!("fullscreen = {}", self.fullscreen),
+            format!("effects = {}", self.effects),
+            format!("key_up = {}", key_name(bindings.up)),
+            format!("key_down = {}", key_name(bindings.down)),
+            format!("key_left = {}", key_name(bindings.left)),
+            format!("key_right = {}", key_name(bindings.right)),
+        ];
+
+        lines.join("\n") + "\n"
+    }
+
+    pub fn load(ctx: &Context) -> Self {
+        match fs::read_to_string(settings_path(ctx)) {
+            Ok(source) => Settings::parse(&source),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(err) => {
+                eprintln!("Failed to load settings: {}", err);
+                Settings::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{Font, Rect};
+use ggez::{Context, GameResult};
+
+use settings::{key_name, GridSize, Settings, SpeedPreset, BINDABLE_KEYS};
+use theme::{Palette, Theme};
+use ui::{Button, Center, Label, List, Message, Slider, Stack, Toggle, Ui, UiEvent};
+
+const COLUMN_WIDTH: f32 = 220.0;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Binding {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Binding {
+    fn all() -> [Binding; 4] {
+        [Binding::Up, Binding::Down, Binding::Left, Binding::Right]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Binding::Up => "Up",
+            Binding::Down => "Down",
+            Binding::Left => "Left",
+            Binding::Right => "Right",
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            Binding::Up => "bind-up",
+            Binding::Down => "bind-down",
+            Binding::Left => "bind-left",
+            Binding::Right => "bind-right",
+        }
+    }
+
+    fn key(self, settings: &mut Settings) -> &mut KeyCode {
+        let bindings = &mut settings.key_bindings;
+        match self {
+            Binding::Up => &mut bindings.up,
+            Binding::Down => &mut bindings.down,
+            Binding::Left => &mut bindings.left,
+            Binding::Right => &mut bindings.right,
+        }
+    }
+}
+
+fn choice_list(id: &'static str, items: Vec<String>, selected: usize, font: Font) -> List {
+    let rows = items.len();
+    let mut list = List::new(id, items, font, COLUMN_WIDTH, rows);
+    list.select(selected);
+    list
+}
+
+fn position<T: PartialEq>(all: &[T], value: &T) -> usize {
+    all.iter().position(|item| item == value).unwrap_or(0)
+}
+
+fn build_ui(
+    settings: &mut Settings,
+    themes: &[Theme],
+    capturing: Option<Binding>,
+    area: Rect,
+    font: Font,
+) -> Ui {
+    let speeds = SpeedPreset::all();
+    let sizes = GridSize::all();
+
+    let choices = Stack::vertical(6.0)
+        .with(Label::new("Speed", font, COLUMN_WIDTH))
+        .with(choice_list(
+            "speed",
+            speeds
+                .iter()
+                .map(|speed| speed.name().to_string())
+                .collect(),
+            position(&speeds, &settings.speed),
+            font,
+        ))
+        .with(Label::new("Grid size", font, COLUMN_WIDTH))
+        .with(choice_list(
+            "grid",
+            sizes
+                .iter()
+                .map(|size| format!("{} ({}x{})", size.name(), size.size().x, size.size().y))
+                .collect(),
+            position(&sizes, &settings.grid_size),
+            font,
+        ))
+        .with(Label::new("Theme", font, COLUMN_WIDTH))
+        .with(choice_list(
+            "theme",
+            themes
+                .iter()
+                .map(|theme| theme.name().to_string())
+                .collect(),
+            position(themes, &settings.theme),
+            font,
+        ));
+
+    let toggles = Stack::vertical(6.0)
+        .with(Slider::new(
+            "volume",
+            "Volume",
+            (settings.volume * 100.0).round(),
+            (0.0, 100.0, 10.0),
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(Toggle::new(
+            "grid_lines",
+            "Grid lines",
+            settings.grid_lines,
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(Toggle::new(
+            "interpolation",
+            "Smooth movement",
+            settings.smooth_movement,
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(Toggle::new(
+            "fullscreen",
+            "Fullscreen",
+            settings.fullscreen,
+            font,
+            COLUMN_WIDTH,
+        ))
+        .with(Toggle::new(
+            "effects",
+            "Effects",
+            settings.effects,
+            font,
+            COLUMN_WIDTH,
+        ));
+
+    let mut controls = Stack::vertical(6.0).with(Label::new("Controls", font, COLUMN_WIDTH));
+    for &binding in &Binding::all() {
+        let text = if capturing == Some(binding) {
+            format!("{}: press a key", binding.name())
+        } else {
+            format!("{}: {}", binding.name(), key_name(*binding.key(settings)))
+        };
+        let button = Button::new(&text, 36.0, font, Rect::new(0.0, 0.0, COLUMN_WIDTH, 36.0));
+        controls.push(button.with_id(binding.id()));
+    }
+    let back = Button::new("Back", 36.0, font, Rect::new(0.0, 0.0, COLUMN_WIDTH, 36.0));
+    controls.push(back.with_id("back"));
+
+    let columns = Stack::horizontal(30.0)
+        .with(choices)
+        .with(toggles)
+        .with(controls);
+    let root = Stack::vertical(16.0)
+        .with(Label::new("Settings", font, 3.0 * COLUMN_WIDTH))
+        .with(columns)
+        .with(Label::new(
+            "Tab / arrows to move, Enter to select, Esc to go back",
+            font,
+            3.0 * COLUMN_WIDTH + 60.0,
+        ));
+
+    Ui::new(Center::new(root), area)
+}
+
+pub struct SettingsScreen {
+    pub settings: Settings,
+    pub closed: bool,
+    ui: Ui,
+    // The custom theme is only offered when a skin defines it
+    themes: Vec<Theme>,
+    area: Rect,
+    capturing: Option<Binding>,
+    font: Font,
+}
+
+impl SettingsScreen {
+    pub fn new(mut settings: Settings, themes: Vec<Theme>, area: Rect, font: Font) -> Self {
+        let mut ui = build_ui(&mut settings, &themes, None, area, font);
+        ui.set_focus(Some(0));
+
+        SettingsScreen {
+            settings,
+            closed: false,
+            ui,
+            themes,
+            area,
+            capturing: None,
+            font,
+        }
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        let focus = self.ui.focus();
+        self.ui = build_ui(
+            &mut self.settings,
+            &self.themes,
+            self.capturing,
+            self.area,
+            self.font,
+        );
+        self.ui.set_focus(focus);
+    }
+
+    pub fn update(&mut self, area: Rect) {
+        if self.area != area {
+            self.area = area;
+            self.rebuild();
+        }
+
+        for message in self.ui.poll() {
+            match message {
+                Message::Selected("speed", index) => {
+                    self.settings.speed = SpeedPreset::all()[index]
+                }
+                Message::Selected("grid", index) => {
+                    self.settings.grid_size = GridSize::all()[index]
+                }
+                Message::Selected("theme", index) => self.settings.theme = self.themes[index],
+                Message::Changed("volume", value) => self.settings.volume = value / 100.0,
+                Message::Toggled("grid_lines", on) => self.settings.grid_lines = on,
+                Message::Toggled("interpolation", on) => self.settings.smooth_movement = on,
+                Message::Toggled("fullscreen", on) => self.settings.fullscreen = on,
+                Message::Toggled("effects", on) => self.settings.effects = on,
+                Message::Clicked("back") => self.closed = true,
+                Message::Clicked(id) => {
+                    self.capturing = Binding::all().iter().cloned().find(|b| b.id() == id);
+                    self.rebuild();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.ui.draw(ctx, palette)
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, keymods: KeyMods) {
+        if let Some(binding) = self.capturing {
+            if BINDABLE_KEYS.contains(&keycode) {
+                // A key taken by another direction swaps the two bindings
+                let old = *binding.key(&mut self.settings);
+                for other in &Binding::all() {
+                    let key = other.key(&mut self.settings);
+                    if *key == keycode {
+                        *key = old;
+                    }
+                }
+                *binding.key(&mut self.settings) = keycode;
+            }
+            self.capturing = None;
+            self.rebuild();
+            return;
+        }
+
+        if keycode == KeyCode::Escape {
+            self.closed = true;
+        } else {
+            self.ui.handle(UiEvent::Key(keycode, keymods));
+        }
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseDown(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseUp(mb, x, y));
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.ui.handle(UiEvent::MouseMotion(x, y));
+    }
+}
@@ -1,29 +1,62 @@
 use base_types::*;
 use constants::*;
-use std::time::Instant;
+use grid::*;
+use power_up::{ActivePowerUp, PowerUpKind};
+use rand::Rng;
 use utils::random_pos;
 
 #[derive(Debug)]
 pub struct Snake {
     pub tail: Vec<GridVector>,
+    pub previous_tail: Vec<GridVector>,
     pub velocity: GridVector,
     pub speed: f64,
-    pub last_round: Instant,
-    pub lost_at: Option<Instant>,
+    pub lost_at: Option<f64>,
     pub score: u32,
     pub grow: GridUnit,
+    pub power_ups: Vec<ActivePowerUp>,
 }
 
 impl Snake {
-    pub fn new() -> Self {
+    pub fn new<R: Rng>(rng: &mut R, grid: GridVector) -> Self {
+        Snake::at(random_pos(rng, &play_area(grid)))
+    }
+
+    pub fn at(start: GridVector) -> Self {
         Snake {
-            tail: vec![random_pos(&PLAY_AREA)],
+            tail: vec![start],
+            previous_tail: vec![start],
             velocity: INITIAL_VELOCITY,
             speed: INITIAL_SPEED,
-            last_round: Instant::now(),
             lost_at: None,
             score: 0,
             grow: INITIAL_SIZE - 1,
+            power_ups: vec![],
         }
     }
+
+    pub fn head(&self) -> GridVector {
+        self.tail[self.tail.len() - 1]
+    }
+
+    pub fn activate_power_up(&mut self, kind: PowerUpKind, time: f64) {
+        self.consume_power_up(kind);
+        self.power_ups.push(ActivePowerUp {
+            kind,
+            activated_at: time,
+        });
+    }
+
+    pub fn consume_power_up(&mut self, kind: PowerUpKind) {
+        self.power_ups.retain(|power_up| power_up.kind != kind);
+    }
+
+    pub fn expire_power_ups(&mut self, time: f64) {
+        self.power_ups
+            .retain(|power_up| time - power_up.activated_at < power_up.kind.duration());
+    }
+
+    pub fn has_power_up(&self, kind: PowerUpKind) -> bool {
+        self.power_ups.iter().any(|power_up| power_up.kind == kind)
+    }
 }
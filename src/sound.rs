@@ -0,0 +1,94 @@
+use std::f32::consts::PI;
+
+use ggez::audio::{SoundData, SoundSource, Source};
+use ggez::{Context, GameResult};
+
+use game_event::GameEvent;
+
+const SAMPLE_RATE: u32 = 22050;
+const AMPLITUDE: f32 = 16_000.0;
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&[value as u8, (value >> 8) as u8]);
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    push_u16(bytes, value as u16);
+    push_u16(bytes, (value >> 16) as u16);
+}
+
+// Renders a sequence of (frequency, seconds) notes as a 16 bit mono WAV file
+fn synthesize(notes: &[(f32, f32)]) -> Vec<u8> {
+    let mut samples: Vec<i16> = vec![];
+    for &(frequency, length) in notes {
+        let count = (length * SAMPLE_RATE as f32) as usize;
+        for i in 0..count {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let envelope = 1.0 - i as f32 / count as f32;
+            let value = (t * frequency * PI * 2.0).sin() * envelope;
+            samples.push((value * AMPLITUDE) as i16);
+        }
+    }
+
+    let data_size = samples.len() as u32 * 2;
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"RIFF");
+    push_u32(&mut bytes, 36 + data_size);
+    bytes.extend_from_slice(b"WAVEfmt ");
+    push_u32(&mut bytes, 16);
+    push_u16(&mut bytes, 1);
+    push_u16(&mut bytes, 1);
+    push_u32(&mut bytes, SAMPLE_RATE);
+    push_u32(&mut bytes, SAMPLE_RATE * 2);
+    push_u16(&mut bytes, 2);
+    push_u16(&mut bytes, 16);
+    bytes.extend_from_slice(b"data");
+    push_u32(&mut bytes, data_size);
+    for sample in samples {
+        push_u16(&mut bytes, sample as u16);
+    }
+
+    bytes
+}
+
+fn source(ctx: &mut Context, notes: &[(f32, f32)]) -> GameResult<Source> {
+    Source::from_data(ctx, SoundData::from_bytes(&synthesize(notes)))
+}
+
+pub struct Sounds {
+    eat: Source,
+    power_up: Source,
+    crash: Source,
+}
+
+impl Sounds {
+    pub fn new(ctx: &mut Context, volume: f32) -> GameResult<Self> {
+        let mut sounds = Sounds {
+            eat: source(ctx, &[(660.0, 0.05), (880.0, 0.07)])?,
+            power_up: source(ctx, &[(520.0, 0.06), (780.0, 0.06), (1040.0, 0.1)])?,
+            crash: source(ctx, &[(180.0, 0.12), (110.0, 0.25)])?,
+        };
+        sounds.set_volume(volume);
+        Ok(sounds)
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.eat.set_volume(volume);
+        self.power_up.set_volume(volume);
+        self.crash.set_volume(volume);
+    }
+
+    pub fn play(&mut self, event: &GameEvent) -> GameResult<()> {
+        match *event {
+            GameEvent::AppleEaten { .. } => self.eat.play_detached(),
+            GameEvent::PowerUpCollected { .. } => self.power_up.play_detached(),
+            GameEvent::StoneDestroyed { .. } | GameEvent::Collision { .. } => {
+                self.crash.play_detached()
+            }
+            GameEvent::Turned { .. }
+            | GameEvent::BoardFilled
+            | GameEvent::Died { .. }
+            | GameEvent::RoundEnded { .. } => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use ggez::event::{KeyCode, KeyMods};
+use ggez::graphics::{draw, Align, Font, Rect, Scale, Text, TextFragment};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use constants::*;
+use frame::Frame;
+use stream::{Spectator, StreamEvent};
+use theme::Palette;
+
+// Shows a streamed game read-only
+pub struct SpectatorScreen {
+    pub closed: bool,
+    spectator: Option<Spectator>,
+    frame: Option<Frame>,
+    status: String,
+    font: Font,
+}
+
+impl SpectatorScreen {
+    pub fn new(address: &str, font: Font) -> Self {
+        let (spectator, status) = match Spectator::connect(address) {
+            Ok(spectator) => (Some(spectator), format!("Waiting for {}", address)),
+            Err(err) => {
+                eprintln!("Failed to connect to stream: {}", err);
+                (None, format!("Failed to connect to {}: {}", address, err))
+            }
+        };
+
+        SpectatorScreen {
+            closed: false,
+            spectator,
+            frame: None,
+            status,
+            font,
+        }
+    }
+
+    pub fn frame(&self) -> Option<&Frame> {
+        self.frame.as_ref()
+    }
+
+    pub fn update(&mut self) {
+        let events = match self.spectator {
+            Some(ref mut spectator) => spectator.poll(),
+            None => return,
+        };
+
+        for event in events {
+            match event {
+                StreamEvent::Frame(frame) => {
+                    self.status.clear();
+                    self.frame = Some(frame);
+                }
+                StreamEvent::Closed(reason) => {
+                    self.status = format!("Disconnected: {}", reason);
+                    self.spectator = None;
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette, area: Rect) -> GameResult<()> {
+        if let Some(ref frame) = self.frame {
+            frame.draw(ctx, palette, self.font, None)?;
+        }
+
+        // Draw status
+        if !self.status.is_empty() {
+            let frag = TextFragment::new(self.status.as_str()).color(palette.foreground);
+            let mut text = Text::new(frag);
+            text.set_font(self.font, Scale::uniform(FONT_DEFAULT_SIZE));
+            text.set_bounds(na::Point2::new(area.w, area.h), Align::Center);
+            let dest = na::Point2::new(area.x, area.y + area.h / 2.0 - FONT_DEFAULT_SIZE);
+            draw(ctx, &text, (dest,))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn notifiy_key_down(&mut self, keycode: KeyCode, _keymods: KeyMods) {
+        if keycode == KeyCode::Escape {
+            self.closed = true;
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use ggez::{filesystem, Context};
+use std::io::Read;
+
+use constants::*;
+use game_event::{DeathCause, GameEvent};
+use game_mode::GameMode;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ModeStats {
+    pub games_played: u32,
+    pub total_score: u64,
+}
+
+impl ModeStats {
+    pub fn average_score(&self) -> f64 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            self.total_score as f64 / f64::from(self.games_played)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Stats {
+    pub games_played: u32,
+    pub apples_eaten: u32,
+    pub turns: u32,
+    pub longest_snake: usize,
+    pub longest_survival: f64,
+    pub deaths: [u32; 5],
+    pub modes: [ModeStats; 5],
+}
+
+fn cause_index(cause: DeathCause) -> usize {
+    DeathCause::all()
+        .iter()
+        .position(|&other| other == cause)
+        .unwrap_or(0)
+}
+
+fn mode_index(mode: GameMode) -> usize {
+    GameMode::all()
+        .iter()
+        .position(|&other| other == mode)
+        .unwrap_or(0)
+}
+
+impl Stats {
+    pub fn deaths(&self, cause: DeathCause) -> u32 {
+        self.deaths[cause_index(cause)]
+    }
+
+    pub fn mode(&self, mode: GameMode) -> &ModeStats {
+        &self.modes[mode_index(mode)]
+    }
+
+    pub fn handle(&mut self, event: &GameEvent, mode: GameMode) {
+        match *event {
+            GameEvent::AppleEaten { .. } => self.apples_eaten += 1,
+            GameEvent::Turned { .. } => self.turns += 1,
+            GameEvent::Died { cause, .. } => self.deaths[cause_index(cause)] += 1,
+            GameEvent::RoundEnded {
+                length,
+                score,
+                time,
+            } => {
+                self.games_played += 1;
+                self.longest_snake = self.longest_snake.max(length);
+                self.longest_survival = self.longest_survival.max(time);
+
+                let mode = &mut self.modes[mode_index(mode)];
+                mode.games_played += 1;
+                mode.total_score += u64::from(score);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn parse(source: &str) -> Self {
+        let mut stats = Stats::default();
+
+        for line in source.lines() {
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts.next().unwrap_or("").trim();
+
+            match key {
+                "games_played" => stats.games_played = value.parse().unwrap_or(0),
+                "apples_eaten" => stats.apples_eaten = value.parse().unwrap_or(0),
+                "turns" => stats.turns = value.parse().unwrap_or(0),
+                "longest_snake" => stats.longest_snake = value.parse().unwrap_or(0),
+                "longest_survival" => stats.longest_survival = value.parse().unwrap_or(0.0),
+                _ => {}
+            }
+
+            for &cause in &DeathCause::all() {
+                if key == format!("deaths.{}", cause.id()) {
+                    stats.deaths[cause_index(cause)] = value.parse().unwrap_or(0);
+                }
+            }
+            for &mode in &GameMode::all() {
+                let mode_stats = &mut stats.modes[mode_index(mode)];
+                if key == format!("games.{}", mode.id()) {
+                    mode_stats.games_played = value.parse().unwrap_or(0);
+                } else if key == format!("score.{}", mode.id()) {
+                    mode_stats.total_score = value.parse().unwrap_or(0);
+                }
+            }
+        }
+
+        stats
+    }
+
+    pub fn to_source(&self) -> String {
+        let mut lines = vec![
+            format!("games_played = {}", self.games_played),
+            format!("apples_eaten = {}", self.apples_eaten),
+            format!("turns = {}", self.turns),
+            format!("longest_snake = {}", self.longest_snake),
+            format!("longest_survival = {}", self.longest_survival),
+        ];
+        for &cause in &DeathCause::all() {
+            lines.push(format!("deaths.{} = {}", cause.id(), self.deaths(cause)));
+        }
+        for &mode in &GameMode::all() {
+            let mode_stats = self.mode(mode);
+            lines.push(format!("games.{} = {}", mode.id(), mode_stats.games_played));
+            lines.push(format!("score.{} = {}", mode.id(), mode_stats.total_score));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    pub fn load(ctx: &mut Context) -> Self {
+        if let Ok(mut file) = filesystem::open(ctx, STATS_PATH) {
+            let mut content = String::new();
+            if file.read_to_string(&mut content).is_ok() {
+                return Stats::parse(&content);
+            }
+        }
+
+        Stats::default()
+    }
+
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("Games played".to_string(), self.games_played.to_string()),
+            ("Apples eaten".to_string(), self.apples_eaten.to_string()),
+            ("Turns".to_string(), self.turns.to_string()),
+            ("Longest snake".to_string(), self.longest_snake.to_string()),
+            (
+                "Longest survival".to_string(),
+                format!("{:.1}s", self.longest_survival),
+            ),
+        ];
+        for &cause in &DeathCause::all() {
+            rows.push((cause.name().to_string(), self.deaths(cause).to_string()));
+        }
+        for &mode in &GameMode::all() {
+            rows.push((
+                format!("{} average", mode.name()),
+                format!("{:.1}", self.mode(mode).average_score()),
+            ));
+        }
+        rows
+    }
+}
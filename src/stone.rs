@@ -1,24 +1,80 @@
 use base_types::*;
-use constants::*;
+use grid::*;
 
+#[derive(Debug, Clone)]
+pub enum Movement {
+    Fixed,
+    Patrol {
+        path: Vec<GridVector>,
+        interval: f64,
+    },
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Blink {
+    pub on: f64,
+    pub off: f64,
+}
+
+#[derive(Debug, Clone)]
 pub struct Stone {
     pub pos: GridVector,
+    pub movement: Movement,
+    pub blink: Option<Blink>,
+    pub min_score: u32,
 }
 
 impl Stone {
     pub fn new(x: GridUnit, y: GridUnit) -> Self {
         Self {
             pos: GridVector::new(x, y),
+            movement: Movement::Fixed,
+            blink: None,
+            min_score: 0,
+        }
+    }
+
+    pub fn update(&mut self, time: f64) {
+        if let Movement::Patrol { ref path, interval } = self.movement {
+            if path.len() < 2 {
+                return;
+            }
+
+            // Walk the path forth and back again
+            let cycle = path.len() * 2 - 2;
+            let step = (time / interval) as usize % cycle;
+            let index = if step < path.len() {
+                step
+            } else {
+                cycle - step
+            };
+            self.pos = path[index];
+        }
+    }
+
+    pub fn is_spawned(&self, score: u32) -> bool {
+        score >= self.min_score
+    }
+
+    pub fn is_active(&self, time: f64, score: u32) -> bool {
+        if !self.is_spawned(score) {
+            return false;
+        }
+
+        match self.blink {
+            Some(blink) => time % (blink.on + blink.off) < blink.on,
+            None => true,
         }
     }
 }
 
-pub fn level_corners() -> Vec<Stone> {
+pub fn level_corners(grid: GridVector) -> Vec<Stone> {
     let mut vec = vec![];
 
     let corners_length = 5;
-    let top_left = PLAY_AREA.pos - GridVector::new(1, 1);
-    let bottom_right = PLAY_AREA.pos + PLAY_AREA.size;
+    let area = play_area(grid);
+    let top_left = area.pos - GridVector::new(1, 1);
+    let bottom_right = area.pos + area.size;
 
     vec.push(Stone::new(top_left.x, top_left.y));
     for i in 1..corners_length {
@@ -0,0 +1,111 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+
+use constants::*;
+use frame::Frame;
+use net;
+
+// Publishes frames to every connected spectator from a background thread, so
+// the game never waits on the network. A spectator that can't take a frame
+// within STREAM_WRITE_TIMEOUT is dropped instead of delaying the others.
+pub struct Broadcaster {
+    pub address: String,
+    frames: Sender<String>,
+}
+
+impl Broadcaster {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?.to_string();
+
+        let (frames, receiver) = channel::<String>();
+        thread::spawn(move || {
+            let mut spectators: Vec<TcpStream> = vec![];
+            loop {
+                while let Ok((stream, _)) = listener.accept() {
+                    let ready = stream
+                        .set_nonblocking(false)
+                        .and_then(|_| stream.set_write_timeout(Some(STREAM_WRITE_TIMEOUT)));
+                    if ready.is_ok() {
+                        spectators.push(stream);
+                    }
+                }
+
+                match receiver.recv_timeout(STREAM_ACCEPT_INTERVAL) {
+                    Ok(frame) => {
+                        spectators.retain(|stream| (&*stream).write_all(frame.as_bytes()).is_ok())
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        Ok(Broadcaster { address, frames })
+    }
+
+    pub fn publish(&self, frame: &Frame) {
+        let _ = self.frames.send(frame.to_source());
+    }
+}
+
+pub enum StreamEvent {
+    Frame(Frame),
+    Closed(String),
+}
+
+// Receives the frames published on the given address
+pub struct Spectator {
+    pub address: String,
+    events: Receiver<StreamEvent>,
+}
+
+impl Spectator {
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let stream = net::connect(address)?;
+        let (sender, events) = channel();
+        thread::spawn(move || {
+            let reason = match read_frames(stream, &sender) {
+                Ok(()) => "the stream ended".to_string(),
+                Err(err) => err.to_string(),
+            };
+            let _ = sender.send(StreamEvent::Closed(reason));
+        });
+
+        Ok(Spectator {
+            address: address.to_string(),
+            events,
+        })
+    }
+
+    pub fn poll(&mut self) -> Vec<StreamEvent> {
+        let mut events = vec![];
+        while let Ok(event) = self.events.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+}
+
+fn read_frames(stream: TcpStream, sender: &Sender<StreamEvent>) -> io::Result<()> {
+    let mut source = String::new();
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        source.push_str(&line);
+        source.push('\n');
+        if line.trim() != "end" {
+            continue;
+        }
+
+        let frame =
+            Frame::parse(&source).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if sender.send(StreamEvent::Frame(frame)).is_err() {
+            break;
+        }
+        source.clear();
+    }
+    Ok(())
+}
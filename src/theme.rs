@@ -0,0 +1,149 @@
+use std::io::Read;
+
+use ggez::graphics::Color;
+use ggez::{filesystem, Context, GameError, GameResult};
+
+use constants::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Theme {
+    Dark,
+    Light,
+    Amber,
+    Forest,
+    Custom,
+}
+
+// The colors a frame is drawn with
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Palette {
+    pub background: Color,
+    pub foreground: Color,
+}
+
+impl Palette {
+    // The first player keeps the theme color in multiplayer
+    pub fn player(self, id: usize) -> Color {
+        match id % MAX_PLAYERS {
+            0 => self.foreground,
+            1 => Color::new(0.95, 0.33, 0.27, 1.0),
+            2 => Color::new(0.26, 0.6, 0.96, 1.0),
+            _ => Color::new(0.72, 0.45, 0.95, 1.0),
+        }
+    }
+}
+
+const THEMES: [Theme; 5] = [
+    Theme::Dark,
+    Theme::Light,
+    Theme::Amber,
+    Theme::Forest,
+    Theme::Custom,
+];
+
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+impl Theme {
+    pub fn all(with_custom: bool) -> Vec<Theme> {
+        THEMES
+            .iter()
+            .cloned()
+            .filter(|&theme| theme != Theme::Custom || with_custom)
+            .collect()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::Amber => "Amber",
+            Theme::Forest => "Forest",
+            Theme::Custom => "Custom",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Amber => "amber",
+            Theme::Forest => "forest",
+            Theme::Custom => "custom",
+        }
+    }
+
+    // The custom theme falls back to the dark one when no skin defines it
+    pub fn palette(self, custom: Option<Palette>) -> Palette {
+        let (background, foreground) = match self {
+            Theme::Dark => (
+                Color::new(0.0078, 0.0569, 0.0762, 1.0),
+                Color::new(1.0, 1.0, 1.0, 1.0),
+            ),
+            Theme::Light => (
+                Color::new(0.94, 0.93, 0.89, 1.0),
+                Color::new(0.1, 0.12, 0.14, 1.0),
+            ),
+            Theme::Amber => (
+                Color::new(0.09, 0.05, 0.0, 1.0),
+                Color::new(1.0, 0.69, 0.0, 1.0),
+            ),
+            Theme::Forest => (
+                Color::new(0.04, 0.1, 0.06, 1.0),
+                Color::new(0.6, 0.92, 0.55, 1.0),
+            ),
+            Theme::Custom => {
+                return custom.unwrap_or_else(|| Theme::Dark.palette(None));
+            }
+        };
+        Palette {
+            background,
+            foreground,
+        }
+    }
+}
+
+pub fn load_custom_theme(ctx: &mut Context) -> GameResult<Option<Palette>> {
+    if !filesystem::exists(ctx, SKIN_THEME_PATH) {
+        return Ok(None);
+    }
+
+    let mut source = String::new();
+    filesystem::open(ctx, SKIN_THEME_PATH)?.read_to_string(&mut source)?;
+
+    let mut background = None;
+    let mut foreground = None;
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let color = parts.next().and_then(parse_color);
+        match key {
+            "background" => background = color,
+            "foreground" => foreground = color,
+            _ => {}
+        }
+    }
+
+    match (background, foreground) {
+        (Some(background), Some(foreground)) => Ok(Some(Palette {
+            background,
+            foreground,
+        })),
+        _ => Err(GameError::ResourceLoadError(format!(
+            "{}: expected `background = #rrggbb` and `foreground = #rrggbb`",
+            SKIN_THEME_PATH
+        ))),
+    }
+}
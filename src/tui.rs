@@ -0,0 +1,260 @@
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue, Result};
+use rand::random;
+
+use base_types::*;
+use cli::Options;
+use constants::*;
+use frame::Frame;
+use game::Game;
+use game_mode::GameMode;
+use level::Level;
+use level_gen;
+use replay::Replay;
+use settings::SpeedPreset;
+use utils::since;
+
+const EMPTY: char = ' ';
+const BODY: char = 'o';
+const HEAD: char = '@';
+const DEAD: char = 'x';
+const APPLE: char = '*';
+const POWER_UP: char = '+';
+const STONE: char = '#';
+const PORTAL: char = 'O';
+
+// Maps every cell to a character and the snake it belongs to, if any
+fn cells(frame: &Frame) -> Vec<Vec<(char, Option<usize>)>> {
+    let mut cells = vec![vec![(EMPTY, None); frame.grid.x as usize]; frame.grid.y as usize];
+    let mut set = |pos: GridVector, cell: (char, Option<usize>)| {
+        if let Some(row) = cells.get_mut(pos.y as usize) {
+            if let Some(target) = row.get_mut(pos.x as usize) {
+                *target = cell;
+            }
+        }
+    };
+
+    for &pos in &frame.stones {
+        set(pos, (STONE, None));
+    }
+    for &(a, b) in &frame.portals {
+        set(a, (PORTAL, None));
+        set(b, (PORTAL, None));
+    }
+    set(frame.apple, (APPLE, None));
+    if let Some(pos) = frame.power_up {
+        set(pos, (POWER_UP, None));
+    }
+    for (id, snake) in frame.snakes.iter().enumerate() {
+        let body = if snake.alive { BODY } else { DEAD };
+        for &pos in &snake.tail {
+            set(pos, (body, Some(id)));
+        }
+        if let (true, Some(&head)) = (snake.alive, snake.tail.last()) {
+            set(head, (HEAD, Some(id)));
+        }
+    }
+
+    cells
+}
+
+// The board as plain text with a border, two columns per cell so it looks square
+pub fn render(frame: &Frame) -> Vec<String> {
+    let border = format!("+{}+", "-".repeat(frame.grid.x as usize * 2));
+    let mut lines = vec![border.clone()];
+    for row in cells(frame) {
+        let mut line = String::from("|");
+        for (c, _) in row {
+            line.push(c);
+            line.push(' ');
+        }
+        line.push('|');
+        lines.push(line);
+    }
+    lines.push(border);
+    lines
+}
+
+// Puts the terminal back when dropped, also while unwinding from a panic
+struct RawTerminal;
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let left = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let disabled = terminal::disable_raw_mode();
+        if let Err(err) = left.and(disabled) {
+            eprintln!("Failed to restore the terminal: {}", err);
+        }
+    }
+}
+
+fn snake_color(id: usize) -> Color {
+    match id % 4 {
+        0 => Color::Reset,
+        1 => Color::Red,
+        2 => Color::Blue,
+        _ => Color::Magenta,
+    }
+}
+
+fn key_direction(code: KeyCode) -> Option<GridVector> {
+    match code {
+        KeyCode::Up | KeyCode::Char('w') => Some(GridVector::new(0, -1)),
+        KeyCode::Down | KeyCode::Char('s') => Some(GridVector::new(0, 1)),
+        KeyCode::Left | KeyCode::Char('a') => Some(GridVector::new(-1, 0)),
+        KeyCode::Right | KeyCode::Char('d') => Some(GridVector::new(1, 0)),
+        _ => None,
+    }
+}
+
+fn new_game(options: &Options, level: &Option<Level>) -> Game {
+    let seed = options.seed.unwrap_or_else(random);
+    let mode = options.mode.unwrap_or(GameMode::Endless);
+    let grid = options.grid.unwrap_or(DEFAULT_GRID_SIZE);
+    let level = match *level {
+        Some(ref level) => level.clone(),
+        None if mode.config().random_levels => level_gen::generate_for_seed(seed, grid),
+        None => Level::corners(grid),
+    };
+    let speed = options.speed.unwrap_or(SpeedPreset::Normal);
+    Game::new(grid, level, mode, speed.initial_speed(), seed)
+}
+
+fn draw(stdout: &mut Stdout, game: &Game, status: &str) -> Result<()> {
+    let frame = Frame::from_game(game, "");
+    let lines = render(&frame);
+    let cells = cells(&frame);
+
+    for (y, line) in lines.iter().enumerate() {
+        queue!(stdout, MoveTo(0, y as u16))?;
+        // Border rows have no cells
+        let row = match y.checked_sub(1).and_then(|y| cells.get(y)) {
+            Some(row) => row,
+            None => {
+                queue!(stdout, Print(line))?;
+                continue;
+            }
+        };
+
+        queue!(stdout, Print('|'))?;
+        for &(c, owner) in row {
+            match owner {
+                Some(id) => queue!(
+                    stdout,
+                    SetForegroundColor(snake_color(id)),
+                    Print(c),
+                    ResetColor,
+                    Print(' ')
+                )?,
+                None => queue!(stdout, Print(c), Print(' '))?,
+            }
+        }
+        queue!(stdout, Print('|'))?;
+    }
+
+    let info = format!(
+        "Score: {}   {}   {}",
+        game.snake.score,
+        game.mode.name(),
+        status
+    );
+    queue!(
+        stdout,
+        MoveTo(0, lines.len() as u16),
+        Print(info),
+        Clear(ClearType::UntilNewLine)
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn play(
+    stdout: &mut Stdout,
+    options: &Options,
+    level: &Option<Level>,
+    replay: &Option<Replay>,
+) -> Result<Game> {
+    let mut game = match *replay {
+        Some(ref replay) => replay.start(),
+        None => new_game(options, level),
+    };
+    let mut last_tick = Instant::now();
+    let mut inputs = vec![];
+    let mut needs_draw = true;
+
+    loop {
+        let replay_finished = match *replay {
+            Some(ref replay) => game.tick >= replay.ticks,
+            None => false,
+        };
+
+        if needs_draw {
+            let status = if game.is_over() || replay_finished {
+                match *replay {
+                    Some(_) => "Replay finished, q to quit",
+                    None => "Game over, r to restart, q to quit",
+                }
+            } else if !game.is_started() && replay.is_none() {
+                "Arrows or WASD to start, q to quit"
+            } else {
+                ""
+            };
+            draw(stdout, &game, status)?;
+            needs_draw = false;
+        }
+
+        // Once nothing moves any more there is no tick to wait for, only keys
+        let ready = if game.is_over() || replay_finished {
+            true
+        } else {
+            let remaining = (game.snake.speed - since(last_tick)).max(0.0);
+            event::poll(Duration::from_secs_f64(remaining))?
+        };
+        if ready {
+            match event::read()? {
+                Event::Key(key) => match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => return Ok(game),
+                    KeyCode::Char('r') if game.is_over() && replay.is_none() => {
+                        game = new_game(options, level);
+                        inputs.clear();
+                        needs_draw = true;
+                    }
+                    code => inputs.extend(key_direction(code)),
+                },
+                Event::Resize(..) => {
+                    execute!(stdout, Clear(ClearType::All))?;
+                    needs_draw = true;
+                }
+                Event::Mouse(_) => {}
+            }
+        }
+
+        if since(last_tick) >= game.snake.speed && !game.is_over() && !replay_finished {
+            let directions = match *replay {
+                Some(ref replay) => replay.directions(game.tick),
+                None => inputs.clone(),
+            };
+            inputs = vec![];
+            game.step(&directions);
+            game.events.clear();
+            last_tick = Instant::now();
+            needs_draw = true;
+        }
+    }
+}
+
+// Plays in the terminal until the player quits and returns the last round
+pub fn run(options: &Options, level: &Option<Level>, replay: &Option<Replay>) -> Result<Game> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    let _terminal = RawTerminal;
+    execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+
+    play(&mut stdout, options, level, replay)
+}
@@ -0,0 +1,112 @@
+use std::time::Instant;
+
+use ggez::graphics::Color;
+
+use utils::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    CubicOut,
+    BackOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => t * (2.0 - t),
+            Easing::CubicOut => {
+                let t = t - 1.0;
+                t * t * t + 1.0
+            }
+            Easing::BackOut => {
+                let s = 1.70158;
+                let t = t - 1.0;
+                t * t * ((s + 1.0) * t + s) + 1.0
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Tween {
+    pub from: f32,
+    pub to: f32,
+    pub duration: f64,
+    pub delay: f64,
+    pub easing: Easing,
+    pub started_at: Instant,
+}
+
+impl Tween {
+    pub fn new(from: f32, to: f32, duration: f64, easing: Easing) -> Self {
+        Tween {
+            from,
+            to,
+            duration,
+            delay: 0.0,
+            easing,
+            started_at: Instant::now(),
+        }
+    }
+
+    pub fn delayed(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn starting_at(mut self, started_at: Instant) -> Self {
+        self.started_at = started_at;
+        self
+    }
+
+    pub fn elapsed(&self) -> f64 {
+        (since(self.started_at) - self.delay).max(0.0)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed() >= self.duration
+    }
+
+    pub fn blinks(&self) -> bool {
+        blinks(self.elapsed())
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+
+        let t = (self.elapsed() / self.duration).min(1.0) as f32;
+        self.easing.apply(t)
+    }
+
+    pub fn value(&self) -> f32 {
+        lerp(self.from, self.to, self.progress())
+    }
+
+    pub fn retarget(&mut self, to: f32, duration: f64) {
+        self.from = self.value();
+        self.to = to;
+        self.duration = duration;
+        self.delay = 0.0;
+        self.started_at = Instant::now();
+    }
+}
+
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+pub fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    Color {
+        r: lerp(from.r, to.r, t),
+        g: lerp(from.g, to.g, t),
+        b: lerp(from.b, to.b, t),
+        a: lerp(from.a, to.a, t),
+    }
+}
@@ -0,0 +1,211 @@
+use ggez::event::{KeyCode, MouseButton};
+use ggez::graphics::{
+    draw, Align, DrawMode, Font, Mesh, Rect, Scale, StrokeOptions, Text, TextFragment,
+};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use constants::*;
+use theme::Palette;
+use tween::{lerp_color, Easing, Tween};
+use ui::widget::*;
+use utils::*;
+
+pub struct Button {
+    pub id: &'static str,
+    pub text: String,
+    pub font: Font,
+    pub line_hight: f32,
+    pub rect: Rect,
+    pub under_mouse: bool,
+    pub highlighted: bool,
+    pub focused: bool,
+    pub pressed: bool,
+    pub was_clicked: bool,
+    pub opacity: f32,
+    hover: Tween,
+}
+
+impl Button {
+    pub fn new(text: &str, line_hight: f32, font: Font, rect: Rect) -> Self {
+        Button {
+            id: "",
+            text: text.to_string(),
+            font,
+            line_hight,
+            rect,
+            under_mouse: false,
+            highlighted: false,
+            focused: false,
+            pressed: false,
+            was_clicked: false,
+            opacity: 1.0,
+            hover: Tween::new(0.0, 0.0, 0.0, Easing::QuadOut),
+        }
+    }
+
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.id = id;
+        self
+    }
+
+    fn update_hover(&mut self) {
+        let target = if self.under_mouse || self.highlighted || self.focused {
+            1.0
+        } else {
+            0.0
+        };
+        if self.hover.to != target {
+            self.hover.retarget(target, BUTTON_HOVER_TIME);
+        }
+    }
+
+    pub fn set_highlighted(&mut self, highlighted: bool) {
+        self.highlighted = highlighted;
+        self.update_hover();
+    }
+
+    pub fn notifiy_mouse_down(&mut self, mb: MouseButton, x: f32, y: f32) {
+        if mb == MouseButton::Left {
+            self.under_mouse = pos_in_rect(self.rect, x, y);
+            self.update_hover();
+            if self.under_mouse {
+                self.pressed = true;
+            } else {
+                self.pressed = false;
+            }
+        }
+    }
+
+    pub fn notifiy_mouse_up(&mut self, mb: MouseButton, x: f32, y: f32) {
+        if mb == MouseButton::Left {
+            self.under_mouse = pos_in_rect(self.rect, x, y);
+            self.update_hover();
+            if self.under_mouse && self.pressed {
+                self.was_clicked = true;
+            } else {
+                self.pressed = false;
+            }
+        }
+    }
+
+    pub fn notifiy_mouse_motion(&mut self, x: f32, y: f32) {
+        self.under_mouse = pos_in_rect(self.rect, x, y);
+        self.update_hover();
+    }
+
+    pub fn unhandled_click(&mut self) -> bool {
+        let value = self.was_clicked;
+        self.was_clicked = false;
+        value
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        let fill = self.hover.value();
+
+        {
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                scale_rect(self.rect, -1.0),
+                fade(palette.foreground, self.opacity),
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        if fill > 0.0 {
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                self.rect,
+                fade(palette.foreground, fill * self.opacity),
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+                scale_rect(self.rect, -2.0),
+                fade(palette.background, fill * self.opacity),
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        {
+            let dest = na::Point2::new(
+                self.rect.x,
+                self.rect.y + self.rect.h / 2.0 - self.line_hight / 2.0,
+            );
+
+            let color = lerp_color(palette.foreground, palette.background, fill);
+            let frag = TextFragment::new(self.text.to_string()).color(fade(color, self.opacity));
+            let mut text = Text::new(frag);
+
+            text.set_font(self.font, Scale::uniform(FONT_DEFAULT_SIZE));
+            text.set_bounds(na::Point2::new(self.rect.w, self.rect.h), Align::Center);
+
+            draw(ctx, &text, (dest,))?;
+        }
+
+        if self.focused {
+            draw_focus_ring(ctx, palette, self.rect, self.opacity)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Widget for Button {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.rect.w, self.rect.h)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        match *event {
+            UiEvent::MouseDown(mb, x, y) => self.notifiy_mouse_down(mb, x, y),
+            UiEvent::MouseUp(mb, x, y) => self.notifiy_mouse_up(mb, x, y),
+            UiEvent::MouseMotion(x, y) => self.notifiy_mouse_motion(x, y),
+            UiEvent::Key(KeyCode::Return, _) | UiEvent::Key(KeyCode::Space, _) if self.focused => {
+                self.was_clicked = true;
+            }
+            _ => {}
+        }
+
+        if self.unhandled_click() {
+            messages.push(Message::Clicked(self.id));
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        Button::draw(self, ctx, palette)
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.update_hover();
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    fn set_highlighted(&mut self, highlighted: bool) {
+        Button::set_highlighted(self, highlighted);
+    }
+
+    fn id(&self) -> &'static str {
+        self.id
+    }
+}
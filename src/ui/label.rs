@@ -0,0 +1,65 @@
+use ggez::graphics::{Align, Font, Rect};
+use ggez::{Context, GameResult};
+
+use constants::*;
+use theme::Palette;
+use ui::widget::*;
+use utils::*;
+
+pub struct Label {
+    pub text: String,
+    pub font: Font,
+    pub align: Align,
+    pub opacity: f32,
+    width: f32,
+    rect: Rect,
+}
+
+impl Label {
+    pub fn new(text: &str, font: Font, width: f32) -> Self {
+        Label {
+            text: text.to_string(),
+            font,
+            align: Align::Center,
+            opacity: 1.0,
+            width,
+            rect: Rect::new(0.0, 0.0, width, 0.0),
+        }
+    }
+
+    pub fn aligned(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+impl Widget for Label {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.width, 8.0 + FONT_DEFAULT_SIZE)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, _event: &UiEvent, _messages: &mut Vec<Message>) {}
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        draw_text(
+            ctx,
+            &self.text,
+            self.font,
+            self.rect,
+            self.align,
+            fade(palette.foreground, self.opacity),
+        )
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+}
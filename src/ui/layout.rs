@@ -0,0 +1,155 @@
+use std::slice;
+
+use ggez::graphics::Rect;
+use ggez::{Context, GameResult};
+
+use theme::Palette;
+use ui::widget::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+pub struct Stack {
+    pub direction: Direction,
+    pub spacing: f32,
+    children: Vec<Box<dyn Widget>>,
+    rect: Rect,
+}
+
+impl Stack {
+    pub fn new(direction: Direction, spacing: f32) -> Self {
+        Stack {
+            direction,
+            spacing,
+            children: vec![],
+            rect: Rect::new(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    pub fn vertical(spacing: f32) -> Self {
+        Stack::new(Direction::Vertical, spacing)
+    }
+
+    pub fn horizontal(spacing: f32) -> Self {
+        Stack::new(Direction::Horizontal, spacing)
+    }
+
+    pub fn with<W: Widget + 'static>(mut self, child: W) -> Self {
+        self.children.push(Box::new(child));
+        self
+    }
+
+    pub fn push<W: Widget + 'static>(&mut self, child: W) {
+        self.children.push(Box::new(child));
+    }
+}
+
+impl Widget for Stack {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        let gaps = self.spacing * self.children.len().saturating_sub(1) as f32;
+        let sizes = self.children.iter().map(|child| child.size());
+
+        match self.direction {
+            Direction::Horizontal => {
+                sizes.fold((gaps, 0.0), |(w, h), (cw, ch)| (w + cw, h.max(ch)))
+            }
+            Direction::Vertical => sizes.fold((0.0, gaps), |(w, h), (cw, ch)| (w.max(cw), h + ch)),
+        }
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+
+        let mut offset = 0.0;
+        for child in &mut self.children {
+            let (w, h) = child.size();
+            let child_rect = match self.direction {
+                Direction::Horizontal => {
+                    Rect::new(rect.x + offset, rect.y + (rect.h - h) / 2.0, w, h)
+                }
+                Direction::Vertical => {
+                    Rect::new(rect.x + (rect.w - w) / 2.0, rect.y + offset, w, h)
+                }
+            };
+            child.layout(child_rect);
+
+            offset += self.spacing
+                + match self.direction {
+                    Direction::Horizontal => w,
+                    Direction::Vertical => h,
+                };
+        }
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        for child in &mut self.children {
+            child.handle(event, messages);
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        for child in &self.children {
+            child.draw(ctx, palette)?;
+        }
+        Ok(())
+    }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut self.children
+    }
+}
+
+pub struct Center {
+    child: Box<dyn Widget>,
+    rect: Rect,
+}
+
+impl Center {
+    pub fn new<W: Widget + 'static>(child: W) -> Self {
+        Center {
+            child: Box::new(child),
+            rect: Rect::new(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Widget for Center {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        self.child.size()
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+
+        let (w, h) = self.child.size();
+        self.child.layout(Rect::new(
+            rect.x + (rect.w - w) / 2.0,
+            rect.y + (rect.h - h) / 2.0,
+            w,
+            h,
+        ));
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        self.child.handle(event, messages);
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.child.draw(ctx, palette)
+    }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        slice::from_mut(&mut self.child)
+    }
+}
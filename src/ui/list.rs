@@ -0,0 +1,155 @@
+use ggez::event::{KeyCode, MouseButton};
+use ggez::graphics::{draw, Align, DrawMode, Font, Mesh, Rect, StrokeOptions};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use constants::*;
+use theme::Palette;
+use ui::widget::*;
+
+const ROW_HIGHT: f32 = 8.0 + FONT_DEFAULT_SIZE;
+
+pub struct List {
+    pub id: &'static str,
+    pub items: Vec<String>,
+    pub selected: Option<usize>,
+    pub font: Font,
+    pub focused: bool,
+    rows: usize,
+    scroll: usize,
+    width: f32,
+    rect: Rect,
+}
+
+impl List {
+    pub fn new(id: &'static str, items: Vec<String>, font: Font, width: f32, rows: usize) -> Self {
+        List {
+            id,
+            items,
+            selected: None,
+            font,
+            focused: false,
+            rows,
+            scroll: 0,
+            width,
+            rect: Rect::new(0.0, 0.0, width, 0.0),
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index >= self.items.len() {
+            return;
+        }
+
+        self.selected = Some(index);
+        if index < self.scroll {
+            self.scroll = index;
+        } else if index >= self.scroll + self.rows {
+            self.scroll = index + 1 - self.rows;
+        }
+    }
+
+    fn select_and_notify(&mut self, index: usize, messages: &mut Vec<Message>) {
+        if index < self.items.len() && self.selected != Some(index) {
+            self.select(index);
+            messages.push(Message::Selected(self.id, index));
+        }
+    }
+
+    fn row_rect(&self, row: usize) -> Rect {
+        Rect::new(
+            self.rect.x,
+            self.rect.y + row as f32 * ROW_HIGHT,
+            self.rect.w,
+            ROW_HIGHT,
+        )
+    }
+}
+
+impl Widget for List {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.width, self.rows as f32 * ROW_HIGHT)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        match *event {
+            UiEvent::MouseDown(MouseButton::Left, x, y) if pos_in_rect(self.rect, x, y) => {
+                let row = ((y - self.rect.y) / ROW_HIGHT) as usize;
+                let index = self.scroll + row;
+                self.select_and_notify(index, messages);
+            }
+            UiEvent::Key(KeyCode::Up, _) if self.focused => {
+                let index = match self.selected {
+                    Some(index) if index > 0 => index - 1,
+                    _ => 0,
+                };
+                self.select_and_notify(index, messages);
+            }
+            UiEvent::Key(KeyCode::Down, _) if self.focused => {
+                let index = match self.selected {
+                    Some(index) => index + 1,
+                    None => 0,
+                };
+                self.select_and_notify(index, messages);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        let rectangle = Mesh::new_rectangle(
+            ctx,
+            DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+            self.rect,
+            palette.foreground,
+        )?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        let visible = self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(self.rows);
+        for (row, (index, item)) in visible.enumerate() {
+            let rect = self.row_rect(row);
+            let color = if self.selected == Some(index) {
+                let rectangle =
+                    Mesh::new_rectangle(ctx, DrawMode::fill(), rect, palette.foreground)?;
+                draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+                palette.background
+            } else {
+                palette.foreground
+            };
+
+            let text_rect = Rect::new(rect.x + 8.0, rect.y, rect.w - 16.0, rect.h);
+            draw_text(ctx, item, self.font, text_rect, Align::Left, color)?;
+        }
+
+        if self.focused {
+            draw_focus_ring(ctx, palette, self.rect, 1.0)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn captures_key(&self, keycode: KeyCode) -> bool {
+        keycode == KeyCode::Up || keycode == KeyCode::Down
+    }
+}
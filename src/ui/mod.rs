@@ -0,0 +1,167 @@
+mod button;
+mod label;
+mod layout;
+mod list;
+mod slider;
+mod text_input;
+mod toggle;
+mod widget;
+
+pub use self::button::Button;
+pub use self::label::Label;
+pub use self::layout::{Center, Stack};
+pub use self::list::List;
+pub use self::slider::Slider;
+pub use self::text_input::TextInput;
+pub use self::toggle::Toggle;
+pub use self::widget::{Message, UiEvent, Widget};
+
+use ggez::event::{KeyCode, KeyMods};
+use ggez::graphics::Rect;
+use ggez::{Context, GameResult};
+
+use self::widget::pos_in_rect;
+use theme::Palette;
+
+fn visit(widget: &mut dyn Widget, f: &mut dyn FnMut(&mut dyn Widget)) {
+    f(widget);
+    for child in widget.children_mut() {
+        visit(child.as_mut(), f);
+    }
+}
+
+pub struct Ui {
+    root: Box<dyn Widget>,
+    focus: Option<usize>,
+    messages: Vec<Message>,
+}
+
+impl Ui {
+    pub fn new<W: Widget + 'static>(root: W, area: Rect) -> Self {
+        let mut ui = Ui {
+            root: Box::new(root),
+            focus: None,
+            messages: vec![],
+        };
+        ui.layout(area);
+        ui
+    }
+
+    pub fn layout(&mut self, area: Rect) {
+        self.root.layout(area);
+    }
+
+    pub fn visit_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        visit(self.root.as_mut(), f);
+    }
+
+    fn focusable_count(&mut self) -> usize {
+        let mut count = 0;
+        self.visit_mut(&mut |widget| {
+            if widget.is_focusable() {
+                count += 1;
+            }
+        });
+        count
+    }
+
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: Option<usize>) {
+        self.focus = focus;
+
+        let mut index = 0;
+        self.visit_mut(&mut |widget| {
+            if widget.is_focusable() {
+                widget.set_focused(focus == Some(index));
+                index += 1;
+            }
+        });
+    }
+
+    pub fn focus_next(&mut self) {
+        let count = self.focusable_count();
+        if count > 0 {
+            let next = match self.focus {
+                Some(index) => (index + 1) % count,
+                None => 0,
+            };
+            self.set_focus(Some(next));
+        }
+    }
+
+    pub fn focus_previous(&mut self) {
+        let count = self.focusable_count();
+        if count > 0 {
+            let previous = match self.focus {
+                Some(index) => (index + count - 1) % count,
+                None => count - 1,
+            };
+            self.set_focus(Some(previous));
+        }
+    }
+
+    fn focused_captures(&mut self, keycode: KeyCode) -> bool {
+        let focus = self.focus;
+        let mut index = 0;
+        let mut captures = false;
+        self.visit_mut(&mut |widget| {
+            if widget.is_focusable() {
+                if focus == Some(index) {
+                    captures = widget.captures_key(keycode);
+                }
+                index += 1;
+            }
+        });
+        captures
+    }
+
+    fn focus_at(&mut self, x: f32, y: f32) {
+        let mut index = 0;
+        let mut clicked = None;
+        self.visit_mut(&mut |widget| {
+            if widget.is_focusable() {
+                if pos_in_rect(widget.rect(), x, y) {
+                    clicked = Some(index);
+                }
+                index += 1;
+            }
+        });
+
+        if clicked.is_some() {
+            self.set_focus(clicked);
+        }
+    }
+
+    pub fn handle(&mut self, event: UiEvent) {
+        match event {
+            UiEvent::Key(KeyCode::Tab, mods) => {
+                if mods.contains(KeyMods::SHIFT) {
+                    self.focus_previous();
+                } else {
+                    self.focus_next();
+                }
+                return;
+            }
+            UiEvent::Key(keycode, _) if !self.focused_captures(keycode) => match keycode {
+                KeyCode::Up | KeyCode::Left => return self.focus_previous(),
+                KeyCode::Down | KeyCode::Right => return self.focus_next(),
+                _ => {}
+            },
+            UiEvent::MouseDown(_, x, y) => self.focus_at(x, y),
+            _ => {}
+        }
+
+        self.root.handle(&event, &mut self.messages);
+    }
+
+    pub fn poll(&mut self) -> Vec<Message> {
+        self.messages.drain(..).collect()
+    }
+
+    pub fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        self.root.draw(ctx, palette)
+    }
+}
@@ -0,0 +1,177 @@
+use ggez::event::{KeyCode, MouseButton};
+use ggez::graphics::{draw, Align, DrawMode, Font, Mesh, Rect};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use constants::*;
+use theme::Palette;
+use ui::widget::*;
+
+const TRACK_HIGHT: f32 = 4.0;
+const KNOB_SIZE: f32 = 16.0;
+
+pub struct Slider {
+    pub id: &'static str,
+    pub label: String,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub font: Font,
+    pub focused: bool,
+    dragging: bool,
+    width: f32,
+    rect: Rect,
+}
+
+impl Slider {
+    pub fn new(
+        id: &'static str,
+        label: &str,
+        value: f32,
+        (min, max, step): (f32, f32, f32),
+        font: Font,
+        width: f32,
+    ) -> Self {
+        Slider {
+            id,
+            label: label.to_string(),
+            value,
+            min,
+            max,
+            step,
+            font,
+            focused: false,
+            dragging: false,
+            width,
+            rect: Rect::new(0.0, 0.0, width, 0.0),
+        }
+    }
+
+    fn track_rect(&self) -> Rect {
+        Rect::new(
+            self.rect.x + KNOB_SIZE / 2.0,
+            self.rect.y + self.rect.h - KNOB_SIZE / 2.0 - TRACK_HIGHT / 2.0,
+            self.rect.w - KNOB_SIZE,
+            TRACK_HIGHT,
+        )
+    }
+
+    fn set_value(&mut self, value: f32, messages: &mut Vec<Message>) {
+        let steps = ((value - self.min) / self.step).round();
+        let value = (self.min + steps * self.step).max(self.min).min(self.max);
+
+        if value != self.value {
+            self.value = value;
+            messages.push(Message::Changed(self.id, value));
+        }
+    }
+
+    fn set_value_at(&mut self, x: f32, messages: &mut Vec<Message>) {
+        let track = self.track_rect();
+        let offset = if x < track.x {
+            0.0
+        } else if x > track.x + track.w {
+            track.w
+        } else {
+            x - track.x
+        };
+        let value = self.min + offset / track.w * (self.max - self.min);
+        self.set_value(value, messages);
+    }
+
+    fn value_text(&self) -> String {
+        if self.step.fract() == 0.0 {
+            format!("{}: {}", self.label, self.value)
+        } else {
+            format!("{}: {:.2}", self.label, self.value)
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.width, 8.0 + FONT_DEFAULT_SIZE + KNOB_SIZE + 4.0)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        match *event {
+            UiEvent::MouseDown(MouseButton::Left, x, y) if pos_in_rect(self.rect, x, y) => {
+                self.dragging = true;
+                self.set_value_at(x, messages);
+            }
+            UiEvent::MouseMotion(x, _) if self.dragging => self.set_value_at(x, messages),
+            UiEvent::MouseUp(MouseButton::Left, _, _) => self.dragging = false,
+            UiEvent::Key(KeyCode::Left, _) if self.focused => {
+                let value = self.value - self.step;
+                self.set_value(value, messages);
+            }
+            UiEvent::Key(KeyCode::Right, _) if self.focused => {
+                let value = self.value + self.step;
+                self.set_value(value, messages);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        let label_rect = Rect::new(
+            self.rect.x,
+            self.rect.y,
+            self.rect.w,
+            8.0 + FONT_DEFAULT_SIZE,
+        );
+        draw_text(
+            ctx,
+            &self.value_text(),
+            self.font,
+            label_rect,
+            Align::Left,
+            palette.foreground,
+        )?;
+
+        let track = self.track_rect();
+        let rectangle = Mesh::new_rectangle(ctx, DrawMode::fill(), track, palette.foreground)?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        let fraction = if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        };
+        let knob = Rect::new(
+            track.x + fraction * track.w - KNOB_SIZE / 2.0,
+            track.y + TRACK_HIGHT / 2.0 - KNOB_SIZE / 2.0,
+            KNOB_SIZE,
+            KNOB_SIZE,
+        );
+        let rectangle = Mesh::new_rectangle(ctx, DrawMode::fill(), knob, palette.foreground)?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        if self.focused {
+            draw_focus_ring(ctx, palette, self.rect, 1.0)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn captures_key(&self, keycode: KeyCode) -> bool {
+        keycode == KeyCode::Left || keycode == KeyCode::Right
+    }
+}
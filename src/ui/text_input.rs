@@ -0,0 +1,114 @@
+use std::time::Instant;
+
+use ggez::event::KeyCode;
+use ggez::graphics::{draw, Align, DrawMode, Font, Mesh, Rect, StrokeOptions};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use theme::Palette;
+use ui::widget::*;
+use utils::*;
+
+pub struct TextInput {
+    pub id: &'static str,
+    pub text: String,
+    pub max_length: usize,
+    pub font: Font,
+    pub focused: bool,
+    focused_at: Instant,
+    width: f32,
+    rect: Rect,
+}
+
+impl TextInput {
+    pub fn new(id: &'static str, text: &str, max_length: usize, font: Font, width: f32) -> Self {
+        TextInput {
+            id,
+            text: text.to_string(),
+            max_length,
+            font,
+            focused: false,
+            focused_at: Instant::now(),
+            width,
+            rect: Rect::new(0.0, 0.0, width, 0.0),
+        }
+    }
+}
+
+impl Widget for TextInput {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.width, 40.0)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        if !self.focused {
+            return;
+        }
+
+        match *event {
+            UiEvent::Text(character)
+                if !character.is_control() && self.text.chars().count() < self.max_length =>
+            {
+                self.text.push(character);
+                messages.push(Message::TextChanged(self.id, self.text.clone()));
+            }
+            UiEvent::Key(KeyCode::Back, _) if !self.text.is_empty() => {
+                self.text.pop();
+                messages.push(Message::TextChanged(self.id, self.text.clone()));
+            }
+            UiEvent::Key(KeyCode::Return, _) => {
+                messages.push(Message::Submitted(self.id, self.text.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        let rectangle = Mesh::new_rectangle(
+            ctx,
+            DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+            scale_rect(self.rect, -1.0),
+            palette.foreground,
+        )?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        let cursor = if self.focused && since(self.focused_at) % 1.0 < 0.5 {
+            "_"
+        } else {
+            ""
+        };
+        draw_text(
+            ctx,
+            &format!("{}{}", self.text, cursor),
+            self.font,
+            scale_rect(self.rect, -8.0),
+            Align::Left,
+            palette.foreground,
+        )?;
+
+        if self.focused {
+            draw_focus_ring(ctx, palette, self.rect, 1.0)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        if focused && !self.focused {
+            self.focused_at = Instant::now();
+        }
+        self.focused = focused;
+    }
+}
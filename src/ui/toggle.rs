@@ -0,0 +1,117 @@
+use ggez::event::{KeyCode, MouseButton};
+use ggez::graphics::{draw, Align, DrawMode, Font, Mesh, Rect, StrokeOptions};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use theme::Palette;
+use ui::widget::*;
+use utils::*;
+
+const BOX_SIZE: f32 = 22.0;
+
+pub struct Toggle {
+    pub id: &'static str,
+    pub label: String,
+    pub value: bool,
+    pub font: Font,
+    pub focused: bool,
+    width: f32,
+    rect: Rect,
+}
+
+impl Toggle {
+    pub fn new(id: &'static str, label: &str, value: bool, font: Font, width: f32) -> Self {
+        Toggle {
+            id,
+            label: label.to_string(),
+            value,
+            font,
+            focused: false,
+            width,
+            rect: Rect::new(0.0, 0.0, width, 0.0),
+        }
+    }
+
+    fn toggle(&mut self, messages: &mut Vec<Message>) {
+        self.value = !self.value;
+        messages.push(Message::Toggled(self.id, self.value));
+    }
+
+    fn box_rect(&self) -> Rect {
+        Rect::new(
+            self.rect.x + self.rect.w - BOX_SIZE,
+            self.rect.y + (self.rect.h - BOX_SIZE) / 2.0,
+            BOX_SIZE,
+            BOX_SIZE,
+        )
+    }
+}
+
+impl Widget for Toggle {
+    fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    fn size(&self) -> (f32, f32) {
+        (self.width, 40.0)
+    }
+
+    fn layout(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>) {
+        match *event {
+            UiEvent::MouseDown(MouseButton::Left, x, y) if pos_in_rect(self.rect, x, y) => {
+                self.toggle(messages);
+            }
+            UiEvent::Key(KeyCode::Return, _) | UiEvent::Key(KeyCode::Space, _) if self.focused => {
+                self.toggle(messages);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()> {
+        draw_text(
+            ctx,
+            &self.label,
+            self.font,
+            self.rect,
+            Align::Left,
+            palette.foreground,
+        )?;
+
+        let rectangle = Mesh::new_rectangle(
+            ctx,
+            DrawMode::Stroke(StrokeOptions::default().with_line_width(2.0)),
+            self.box_rect(),
+            palette.foreground,
+        )?;
+        draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+
+        if self.value {
+            let rectangle = Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                scale_rect(self.box_rect(), -5.0),
+                palette.foreground,
+            )?;
+            draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))?;
+        }
+
+        if self.focused {
+            draw_focus_ring(ctx, palette, self.rect, 1.0)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
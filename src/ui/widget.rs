@@ -0,0 +1,100 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{
+    draw, Align, Color, DrawMode, Font, Mesh, Rect, Scale, StrokeOptions, Text, TextFragment,
+};
+use ggez::nalgebra as na;
+use ggez::{Context, GameResult};
+
+use constants::*;
+use theme::Palette;
+use utils::*;
+
+#[derive(Debug, Clone)]
+pub enum UiEvent {
+    MouseDown(MouseButton, f32, f32),
+    MouseUp(MouseButton, f32, f32),
+    MouseMotion(f32, f32),
+    Key(KeyCode, KeyMods),
+    Text(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Clicked(&'static str),
+    Toggled(&'static str, bool),
+    Changed(&'static str, f32),
+    TextChanged(&'static str, String),
+    Submitted(&'static str, String),
+    Selected(&'static str, usize),
+}
+
+pub trait Widget {
+    fn rect(&self) -> Rect;
+
+    fn size(&self) -> (f32, f32);
+
+    fn layout(&mut self, rect: Rect);
+
+    fn handle(&mut self, event: &UiEvent, messages: &mut Vec<Message>);
+
+    fn draw(&self, ctx: &mut Context, palette: Palette) -> GameResult<()>;
+
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    fn set_focused(&mut self, _focused: bool) {}
+
+    fn set_opacity(&mut self, _opacity: f32) {}
+
+    fn set_highlighted(&mut self, _highlighted: bool) {}
+
+    fn id(&self) -> &'static str {
+        ""
+    }
+
+    fn captures_key(&self, _keycode: KeyCode) -> bool {
+        false
+    }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn Widget>] {
+        &mut []
+    }
+}
+
+pub fn pos_in_rect(rect: Rect, x: f32, y: f32) -> bool {
+    x > rect.x && y > rect.y && x < rect.x + rect.w && y < rect.y + rect.h
+}
+
+pub fn draw_focus_ring(
+    ctx: &mut Context,
+    palette: Palette,
+    rect: Rect,
+    opacity: f32,
+) -> GameResult<()> {
+    let rectangle = Mesh::new_rectangle(
+        ctx,
+        DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+        scale_rect(rect, 4.0),
+        fade(palette.foreground, opacity),
+    )?;
+    draw(ctx, &rectangle, (na::Point2::new(0.0, 0.0),))
+}
+
+pub fn draw_text(
+    ctx: &mut Context,
+    text: &str,
+    font: Font,
+    rect: Rect,
+    align: Align,
+    color: Color,
+) -> GameResult<()> {
+    let line_hight = 8.0 + FONT_DEFAULT_SIZE;
+    let dest = na::Point2::new(rect.x, rect.y + rect.h / 2.0 - line_hight / 2.0);
+
+    let mut text = Text::new(TextFragment::new(text).color(color));
+    text.set_font(font, Scale::uniform(FONT_DEFAULT_SIZE));
+    text.set_bounds(na::Point2::new(rect.w, rect.h), align);
+
+    draw(ctx, &text, (dest,))
+}
@@ -1,13 +1,20 @@
 use base_types::*;
 use constants::*;
 use ggez::{graphics, timer};
-use rand::random;
+use grid::*;
+use rand::Rng;
 use std::time::Instant;
 
 pub fn since(i: Instant) -> f64 {
     timer::duration_to_f64(i.elapsed())
 }
 
+pub fn ago(seconds: f64) -> Instant {
+    let now = Instant::now();
+    now.checked_sub(timer::f64_to_duration(seconds.max(0.0)))
+        .unwrap_or(now)
+}
+
 pub fn blinks(time: f64) -> bool {
     time % (BLINK_INTERVAL * 2.0) < BLINK_INTERVAL
 }
@@ -23,16 +30,23 @@ pub fn wrap_in(area: &GridArea, point: GridVector) -> GridVector {
     }
 }
 
-pub fn wrap_in_grid(point: GridVector) -> GridVector {
-    wrap_in(&GRID_AREA, point)
+pub fn wrap_in_grid(grid: GridVector, point: GridVector) -> GridVector {
+    wrap_in(&grid_area(grid), point)
+}
+
+pub fn in_area(area: &GridArea, point: GridVector) -> bool {
+    point.x >= area.pos.x
+        && point.y >= area.pos.y
+        && point.x < area.pos.x + area.size.x
+        && point.y < area.pos.y + area.size.y
 }
 
-pub fn random_pos(area: &GridArea) -> GridVector {
+pub fn random_pos<R: Rng>(rng: &mut R, area: &GridArea) -> GridVector {
     wrap_in(
         &area,
         GridVector {
-            x: random::<GridUnit>(),
-            y: random::<GridUnit>(),
+            x: rng.gen::<GridUnit>(),
+            y: rng.gen::<GridUnit>(),
         },
     )
 }
@@ -46,6 +60,36 @@ pub fn gv_to_rect(gv: &GridVector) -> graphics::Rect {
     )
 }
 
+pub fn slide_to_rect(gv: &GridVector, direction: GridVector, fraction: f32) -> graphics::Rect {
+    let rect = gv_to_rect(gv);
+    graphics::Rect::new(
+        rect.x + f32::from(direction.x) * GRID_TILE_SIZE * fraction,
+        rect.y + f32::from(direction.y) * GRID_TILE_SIZE * fraction,
+        rect.w,
+        rect.h,
+    )
+}
+
+pub fn pos_to_gv(grid: GridVector, x: f32, y: f32) -> Option<GridVector> {
+    let gv = GridVector {
+        x: ((x + GRID_TILE_PADDING) / GRID_TILE_SIZE).floor() as GridUnit,
+        y: ((y - INFO_BAR_HIGHT + GRID_TILE_PADDING) / GRID_TILE_SIZE).floor() as GridUnit,
+    };
+
+    if in_area(&grid_area(grid), gv) {
+        Some(gv)
+    } else {
+        None
+    }
+}
+
+pub fn fade(color: graphics::Color, alpha: f32) -> graphics::Color {
+    graphics::Color {
+        a: color.a * alpha,
+        ..color
+    }
+}
+
 pub fn scale_rect(rect: graphics::Rect, diff: f32) -> graphics::Rect {
     graphics::Rect::new(
         rect.x - diff,
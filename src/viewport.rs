@@ -0,0 +1,61 @@
+use ggez::graphics::{set_screen_coordinates, Rect};
+use ggez::{Context, GameResult};
+
+use base_types::*;
+use grid::*;
+
+// Fits the logical window of a grid into the real one
+pub struct Viewport {
+    pub grid: GridVector,
+    pub scale: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub fn new(grid: GridVector, width: f32, height: f32) -> Self {
+        let scale = (width / window_w(grid)).min(height / window_h(grid));
+
+        Viewport {
+            grid,
+            scale,
+            offset_x: (width - window_w(grid) * scale) / 2.0,
+            offset_y: (height - window_h(grid) * scale) / 2.0,
+            width,
+            height,
+        }
+    }
+
+    pub fn area(&self) -> Rect {
+        window_rect(self.grid)
+    }
+
+    pub fn visible_rect(&self) -> Rect {
+        Rect::new(
+            -self.offset_x / self.scale,
+            -self.offset_y / self.scale,
+            self.width / self.scale,
+            self.height / self.scale,
+        )
+    }
+
+    pub fn to_logical(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.offset_x) / self.scale,
+            (y - self.offset_y) / self.scale,
+        )
+    }
+
+    pub fn apply(&self, ctx: &mut Context) -> GameResult<()> {
+        self.apply_offset(ctx, 0.0, 0.0)
+    }
+
+    pub fn apply_offset(&self, ctx: &mut Context, x: f32, y: f32) -> GameResult<()> {
+        let mut rect = self.visible_rect();
+        rect.x -= x;
+        rect.y -= y;
+        set_screen_coordinates(ctx, rect)
+    }
+}
@@ -0,0 +1,118 @@
+// Checks the frames written when exporting a replay
+
+extern crate ggez_snake;
+extern crate gif;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use gif::SetParameter;
+
+use ggez_snake::base_types::*;
+use ggez_snake::constants::*;
+use ggez_snake::export::{export, ExportOptions};
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::level::Level;
+use ggez_snake::replay::Replay;
+use ggez_snake::settings::SpeedPreset;
+use ggez_snake::theme::Theme;
+
+fn replay() -> Replay {
+    let grid = GridVector::new(MIN_GRID_SIZE, MIN_GRID_SIZE);
+    Replay {
+        seed: 5,
+        mode: GameMode::Endless,
+        speed: SpeedPreset::Normal.initial_speed(),
+        grid,
+        level: Level::corners(grid),
+        turns: vec![(0, GridVector::new(1, 0)), (3, GridVector::new(0, 1))],
+        ticks: 6,
+        score: 0,
+    }
+}
+
+fn options(fps: Option<f64>) -> ExportOptions {
+    ExportOptions {
+        scale: 1,
+        fps,
+        theme: Theme::Dark,
+    }
+}
+
+// Seconds the export covers, the last frame is held a little longer
+fn duration(replay: &Replay) -> f64 {
+    replay.simulate().time + EXPORT_FINAL_HOLD
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("ggez-snake-export-{}", name));
+    if path.is_dir() {
+        fs::remove_dir_all(&path).unwrap();
+    }
+    path
+}
+
+fn png_count(name: &str, fps: Option<f64>) -> (usize, usize) {
+    let path = temp_path(name);
+    let frames = export(&replay(), &path, &options(fps)).unwrap();
+    let files = fs::read_dir(&path).unwrap().count();
+    fs::remove_dir_all(&path).unwrap();
+    (frames, files)
+}
+
+// The delay of every frame, in hundredths of a second
+fn gif_delays(name: &str, fps: Option<f64>) -> Vec<u16> {
+    let path = temp_path(name);
+    let frames = export(&replay(), &path, &options(fps)).unwrap();
+
+    let mut decoder = gif::Decoder::new(File::open(&path).unwrap());
+    decoder.set(gif::ColorOutput::Indexed);
+    let mut reader = decoder.read_info().unwrap();
+    let mut delays = vec![];
+    while let Some(frame) = reader.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(delays.len(), frames);
+    delays
+}
+
+#[test]
+fn png_sequences_have_a_frame_per_tick() {
+    let replay = replay();
+    assert_eq!(
+        png_count("ticks", None),
+        (replay.ticks as usize + 1, replay.ticks as usize + 1)
+    );
+}
+
+#[test]
+fn png_sequences_sample_at_the_frame_rate() {
+    let fps = 10.0;
+    let expected = (duration(&replay()) * fps).ceil() as usize;
+    assert_eq!(png_count("fps", Some(fps)), (expected, expected));
+}
+
+#[test]
+fn gifs_show_every_tick_for_its_duration() {
+    let replay = replay();
+    let delays = gif_delays("ticks.gif", None);
+
+    assert_eq!(delays.len(), replay.ticks as usize + 1);
+    let total: u32 = delays.iter().map(|&delay| u32::from(delay)).sum();
+    assert_eq!(total, (duration(&replay) * 100.0).round() as u32);
+}
+
+#[test]
+fn gifs_at_a_frame_rate_use_whole_samples() {
+    let fps = 20.0;
+    let delays = gif_delays("fps.gif", Some(fps));
+
+    // Each frame covers a whole number of samples, 5 hundredths each
+    assert!(delays.iter().all(|&delay| delay > 0 && delay % 5 == 0));
+    let total: u32 = delays.iter().map(|&delay| u32::from(delay)).sum();
+    let samples = (duration(&replay()) * fps).ceil();
+    assert_eq!(total, (samples / fps * 100.0).round() as u32);
+}
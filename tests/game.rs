@@ -0,0 +1,111 @@
+// Checks game rules that are hard to reach by playing
+
+extern crate ggez_snake;
+
+use ggez_snake::base_types::*;
+use ggez_snake::game::Game;
+use ggez_snake::game_event::GameEvent;
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::level::Level;
+use ggez_snake::power_up::{PowerUp, PowerUpKind};
+use ggez_snake::settings::SpeedPreset;
+use ggez_snake::stone::Stone;
+
+#[test]
+fn filling_the_board_ends_the_round() {
+    // Stones cover the play area except for a row of three cells
+    let grid = GridVector::new(16, 16);
+    let mut stones = vec![];
+    for x in 2..14 {
+        for y in 2..14 {
+            if y != 2 || x > 4 {
+                stones.push(Stone::new(x, y));
+            }
+        }
+    }
+    let level = Level {
+        name: "full".to_string(),
+        stones,
+        portals: vec![],
+        spawn_points: vec![],
+        start: Some(GridVector::new(2, 2)),
+    };
+
+    let mut game = Game::new(
+        grid,
+        level,
+        GameMode::Endless,
+        SpeedPreset::Normal.initial_speed(),
+        3,
+    );
+    game.step(&[GridVector::new(1, 0)]);
+    game.step(&[]);
+
+    assert!(game.is_over());
+    for event in &game.events {
+        if let GameEvent::Died { .. } = *event {
+            panic!("a full board is no death");
+        }
+    }
+}
+
+#[test]
+fn apples_never_spawn_on_the_power_up() {
+    // Stones cover the play area except for a row of four cells
+    let grid = GridVector::new(16, 16);
+    let mut stones = vec![];
+    for x in 2..14 {
+        for y in 2..14 {
+            if y != 2 || x > 5 {
+                stones.push(Stone::new(x, y));
+            }
+        }
+    }
+    let level = Level {
+        name: "row".to_string(),
+        stones,
+        portals: vec![],
+        spawn_points: vec![],
+        start: Some(GridVector::new(2, 2)),
+    };
+
+    for seed in 0..20 {
+        let mut game = Game::new(
+            grid,
+            level.clone(),
+            GameMode::Endless,
+            SpeedPreset::Normal.initial_speed(),
+            seed,
+        );
+        game.apple.position = GridVector::new(3, 2);
+        game.power_up = Some(PowerUp::new(PowerUpKind::Ghost, GridVector::new(5, 2), 0.0));
+        game.step(&[GridVector::new(1, 0)]);
+
+        assert_eq!(game.apple.position, GridVector::new(4, 2));
+    }
+}
+
+#[test]
+fn a_round_ends_once() {
+    let grid = GridVector::new(32, 20);
+    let mut game = Game::new(
+        grid,
+        Level::corners(grid),
+        GameMode::Zen,
+        SpeedPreset::Normal.initial_speed(),
+        5,
+    );
+    game.end_round();
+    assert!(game.events.is_empty());
+
+    game.step(&[GridVector::new(1, 0)]);
+    game.end_round();
+    game.end_round();
+    let mut ended = 0;
+    for event in &game.events {
+        if let GameEvent::RoundEnded { .. } = *event {
+            ended += 1;
+        }
+    }
+    assert_eq!(ended, 1);
+}
@@ -0,0 +1,115 @@
+// Checks the server side validation of submitted scores
+
+extern crate ggez_snake;
+
+use std::env;
+use std::fs;
+
+use ggez_snake::base_types::*;
+use ggez_snake::game::Game;
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::leaderboard::{parse_submission, validate, Board, Entry};
+use ggez_snake::level::Level;
+use ggez_snake::replay::Replay;
+use ggez_snake::settings::{GridSize, SpeedPreset};
+use ggez_snake::stone::Stone;
+
+fn recorded(level: Level) -> Replay {
+    let grid = GridSize::Medium.size();
+    let mut game = Game::new(
+        grid,
+        level,
+        GameMode::Endless,
+        SpeedPreset::Normal.initial_speed(),
+        11,
+    );
+    let turns = [(0, GridVector::new(1, 0)), (5, GridVector::new(0, 1))];
+    while game.tick < 20 {
+        let directions: Vec<GridVector> = turns
+            .iter()
+            .filter(|&&(tick, _)| tick == game.tick)
+            .map(|&(_, direction)| direction)
+            .collect();
+        game.step(&directions);
+    }
+    Replay::record(&game)
+}
+
+fn honest() -> Replay {
+    recorded(Level::corners(GridSize::Medium.size()))
+}
+
+fn submission(name: &str, replay: &Replay) -> String {
+    format!("player {}\n{}", name, replay.to_source())
+}
+
+#[test]
+fn accepts_an_honest_replay() {
+    let (name, replay) = parse_submission(&submission("Ada", &honest())).unwrap();
+    assert_eq!(name, "Ada");
+    let entry = validate(&replay).unwrap();
+    assert_eq!(entry.mode, GameMode::Endless);
+    assert_eq!(entry.score, replay.score);
+}
+
+#[test]
+fn rejects_an_inflated_score() {
+    let mut replay = honest();
+    replay.score += 5;
+    let (_, replay) = parse_submission(&submission("Ada", &replay)).unwrap();
+    assert!(validate(&replay).is_err());
+}
+
+#[test]
+fn rejects_an_unknown_level() {
+    let mut level = Level::corners(GridSize::Medium.size());
+    level.stones.push(Stone::new(30, 20));
+    assert!(validate(&recorded(level)).is_err());
+}
+
+#[test]
+fn rejects_an_unknown_speed() {
+    let mut replay = honest();
+    replay.speed *= 3.0;
+    assert!(validate(&replay).is_err());
+}
+
+#[test]
+fn rejects_an_unknown_grid() {
+    let mut replay = honest();
+    replay.grid = GridVector::new(200, 200);
+    replay.level = Level::corners(replay.grid);
+    assert!(validate(&replay).is_err());
+}
+
+#[test]
+fn rejects_control_characters_in_names() {
+    assert!(parse_submission(&submission("Ada\u{7}", &honest())).is_err());
+    assert!(parse_submission(&submission("Ada\rEve", &honest())).is_err());
+}
+
+#[test]
+fn board_ranks_and_stores_entries() {
+    let path = env::temp_dir().join("ggez-snake-board.txt");
+    let _ = fs::remove_file(&path);
+    let entry = |mode, score, name: &str| Entry {
+        mode,
+        score,
+        name: name.to_string(),
+    };
+
+    let mut board = Board::load(&path).unwrap();
+    assert_eq!(board.insert(entry(GameMode::Endless, 5, "Ada")).unwrap(), 1);
+    assert_eq!(board.insert(entry(GameMode::Endless, 9, "Eve")).unwrap(), 1);
+    assert_eq!(board.insert(entry(GameMode::Endless, 7, "Bob")).unwrap(), 2);
+    assert_eq!(board.insert(entry(GameMode::Zen, 1, "Ada")).unwrap(), 1);
+
+    let board = Board::load(&path).unwrap();
+    let names: Vec<String> = board
+        .top(GameMode::Endless, 2)
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+    assert_eq!(names, vec!["Eve", "Bob"]);
+    assert_eq!(board.top(GameMode::Zen, 10).len(), 1);
+}
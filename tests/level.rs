@@ -0,0 +1,27 @@
+// Checks the timing options of stones in level files
+
+extern crate ggez_snake;
+
+use ggez_snake::level::Level;
+use ggez_snake::settings::GridSize;
+
+fn parse(stone: &str) -> bool {
+    Level::parse("test", stone, GridSize::Medium.size()).is_ok()
+}
+
+#[test]
+fn patrol_intervals_must_be_positive() {
+    assert!(parse("stone 5 5 patrol=8,5 interval=0.5"));
+    assert!(!parse("stone 5 5 patrol=8,5 interval=0"));
+    assert!(!parse("stone 5 5 patrol=8,5 interval=-1"));
+    assert!(!parse("stone 5 5 patrol=8,5 interval=NaN"));
+}
+
+#[test]
+fn blink_cycles_must_not_be_empty() {
+    assert!(parse("stone 5 5 blink=1,2"));
+    assert!(parse("stone 5 5 blink=1,0"));
+    assert!(!parse("stone 5 5 blink=0,0"));
+    assert!(!parse("stone 5 5 blink=-1,2"));
+    assert!(!parse("stone 5 5 blink=2,-1"));
+}
@@ -0,0 +1,103 @@
+// Compares rendered games with the images in tests/golden. Run with
+// UPDATE_GOLDEN=1 to write new images after an intended change.
+
+extern crate ggez_snake;
+
+use std::env;
+use std::path::PathBuf;
+
+use ggez_snake::base_types::*;
+use ggez_snake::game::Game;
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::level::Level;
+use ggez_snake::render::{Pixmap, Renderer};
+use ggez_snake::replay::Replay;
+use ggez_snake::settings::SpeedPreset;
+use ggez_snake::theme::Theme;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.png", name))
+}
+
+fn play(
+    grid: GridVector,
+    mode: GameMode,
+    seed: u64,
+    turns: &[(u64, GridVector)],
+    ticks: u64,
+) -> Game {
+    let replay = Replay {
+        seed,
+        mode,
+        speed: SpeedPreset::Normal.initial_speed(),
+        grid,
+        level: Level::corners(grid),
+        turns: turns.to_vec(),
+        ticks,
+        score: 0,
+    };
+    replay.simulate()
+}
+
+fn check(name: &str, actual: &Pixmap) {
+    let path = golden_path(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save_png(&path).unwrap();
+        return;
+    }
+
+    let expected = Pixmap::load_png(&path).unwrap();
+    if *actual != expected {
+        let failed = env::temp_dir().join(format!("{}.actual.png", name));
+        actual.save_png(&failed).unwrap();
+        panic!(
+            "{} differs from {}, see {}",
+            name,
+            path.display(),
+            failed.display()
+        );
+    }
+}
+
+#[test]
+fn golden_endless() {
+    let grid = GridVector::new(32, 20);
+    let right = GridVector::new(1, 0);
+    let down = GridVector::new(0, 1);
+    let game = play(
+        grid,
+        GameMode::Endless,
+        7,
+        &[(0, right), (6, down), (10, right)],
+        14,
+    );
+    check(
+        "endless",
+        &Renderer::new(Theme::Dark.palette(None)).render(&game, "player"),
+    );
+}
+
+#[test]
+fn golden_time_attack_light() {
+    let grid = GridVector::new(24, 16);
+    let up = GridVector::new(0, -1);
+    let right = GridVector::new(1, 0);
+    let game = play(grid, GameMode::TimeAttack, 42, &[(0, up), (3, right)], 30);
+    check(
+        "time_attack_light",
+        &Renderer::new(Theme::Light.palette(None)).render(&game, ""),
+    );
+}
+
+#[test]
+fn png_round_trip() {
+    let palette = Theme::Amber.palette(None);
+    let mut pixmap = Pixmap::new(4, 3, palette.background);
+    pixmap.blend(1, 2, palette.foreground, 0.5);
+    let path = env::temp_dir().join("ggez-snake-round-trip.png");
+    pixmap.save_png(&path).unwrap();
+    assert_eq!(Pixmap::load_png(&path).unwrap(), pixmap);
+}
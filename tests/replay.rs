@@ -0,0 +1,55 @@
+// Checks that replays survive a trip through their file format
+
+extern crate ggez_snake;
+
+use ggez_snake::base_types::*;
+use ggez_snake::game::Game;
+use ggez_snake::game_mode::GameMode;
+use ggez_snake::level::Level;
+use ggez_snake::replay::Replay;
+use ggez_snake::settings::SpeedPreset;
+
+fn record(mode: GameMode, seed: u64) -> Replay {
+    let grid = GridVector::new(32, 20);
+    let mut game = Game::new(
+        grid,
+        Level::corners(grid),
+        mode,
+        SpeedPreset::Fast.initial_speed(),
+        seed,
+    );
+    // Heads for the apple, so the recording has turns and a score
+    while game.tick < 200 && !game.is_over() {
+        let head = game.snake.head();
+        let apple = game.apple.position;
+        let direction = if apple.x != head.x {
+            GridVector::new((apple.x - head.x).signum(), 0)
+        } else {
+            GridVector::new(0, (apple.y - head.y).signum())
+        };
+        game.step(&[direction]);
+    }
+    Replay::record(&game)
+}
+
+#[test]
+fn a_parsed_replay_plays_the_same_game() {
+    for &mode in GameMode::all().iter() {
+        let replay = record(mode, 17);
+        assert!(replay.score > 0);
+        let parsed = Replay::parse(&replay.to_source()).unwrap();
+
+        assert_eq!(parsed.seed, replay.seed);
+        assert_eq!(parsed.mode, replay.mode);
+        assert_eq!(parsed.grid, replay.grid);
+        assert_eq!(parsed.turns, replay.turns);
+        assert!(parsed.matches(&parsed.simulate()));
+    }
+}
+
+#[test]
+fn a_changed_score_no_longer_matches() {
+    let mut replay = Replay::parse(&record(GameMode::Endless, 3).to_source()).unwrap();
+    replay.score += 1;
+    assert!(!replay.matches(&replay.simulate()));
+}
@@ -0,0 +1,51 @@
+// Checks the text the terminal frontend draws for a board
+
+extern crate ggez_snake;
+
+use ggez_snake::base_types::*;
+use ggez_snake::frame::{Frame, FrameSnake};
+use ggez_snake::tui::render;
+
+fn snake(alive: bool, tail: Vec<GridVector>) -> FrameSnake {
+    FrameSnake {
+        name: String::new(),
+        score: 0,
+        alive,
+        tail,
+    }
+}
+
+#[test]
+fn renders_a_fixed_frame() {
+    let frame = Frame {
+        grid: GridVector::new(6, 4),
+        tick: 0,
+        snakes: vec![
+            snake(
+                true,
+                vec![
+                    GridVector::new(1, 1),
+                    GridVector::new(2, 1),
+                    GridVector::new(3, 1),
+                ],
+            ),
+            snake(false, vec![GridVector::new(1, 3), GridVector::new(2, 3)]),
+        ],
+        apple: GridVector::new(3, 0),
+        power_up: Some(GridVector::new(4, 2)),
+        stones: vec![GridVector::new(0, 0)],
+        portals: vec![(GridVector::new(0, 2), GridVector::new(5, 3))],
+    };
+
+    assert_eq!(
+        render(&frame),
+        vec![
+            "+------------+",
+            "|#     *     |",
+            "|  o o @     |",
+            "|O       +   |",
+            "|  x x     O |",
+            "+------------+",
+        ]
+    );
+}